@@ -0,0 +1,486 @@
+//! Contains checksum algorithms, used by `checksummed`.
+//! 
+//! # Examples
+//! ```
+//! use std::io::Cursor;
+//! use bin_io::numbers::{ be_u8, be_u16, le_u32 };
+//! use bin_io::checksum::Crc32;
+//! use bin_io::{ seq, checksummed, read, write };
+//! 
+//! #[derive(Debug, PartialEq, Eq)]
+//! struct Record {
+//!     a: u8,
+//!     b: u16
+//! }
+//! 
+//! let record = Record { a: 0x10, b: 0x20 };
+//! 
+//! let vec = Vec::new();
+//! let mut cursor = Cursor::new(vec);
+//! 
+//! write(&mut cursor, &record, checksummed!(
+//!     seq!(
+//!         Record { a, b },
+//!         a: be_u8() =>
+//!         b: be_u16() =>
+//!     ),
+//!     le_u32(),
+//!     Crc32::ieee()
+//! )).unwrap();
+//! 
+//! assert_eq!(cursor.get_ref().len(), 7);
+//! 
+//! cursor.set_position(0);
+//! 
+//! let other = read(&mut cursor, checksummed!(
+//!     seq!(
+//!         Record { a, b },
+//!         a: be_u8() =>
+//!         b: be_u16() =>
+//!     ),
+//!     le_u32(),
+//!     Crc32::ieee()
+//! )).unwrap();
+//! 
+//! assert_eq!(other, record);
+//! 
+//! // Corrupt the record
+//! cursor.get_mut()[0] = 0x11;
+//! cursor.set_position(0);
+//! 
+//! let err = read(&mut cursor, checksummed!(
+//!     seq!(
+//!         Record { a, b },
+//!         a: be_u8() =>
+//!         b: be_u16() =>
+//!     ),
+//!     le_u32(),
+//!     Crc32::ieee()
+//! ));
+//! 
+//! assert!(err.is_err());
+//! ```
+
+use crate::{ ReadFn, WriteFn, BinError, size };
+use std::io::{ self, Read, Write, Error };
+
+/// Trait representing a checksum algorithm.
+/// 
+/// # Examples
+/// ```
+/// use bin_io::checksum::Checksum;
+/// 
+/// #[derive(Clone, Default)]
+/// struct Sum16 {
+///     sum: u16
+/// }
+/// 
+/// impl Checksum for Sum16 {
+///     type Output = u16;
+/// 
+///     fn update(&mut self, data: &[u8]) {
+///         for b in data {
+///             self.sum = self.sum.wrapping_add(*b as u16);
+///         }
+///     }
+/// 
+///     fn finish(&self) -> u16 {
+///         self.sum
+///     }
+/// }
+/// ```
+pub trait Checksum {
+    /// Type of the computed checksum.
+    type Output;
+
+    /// Feeds data to the algorithm.
+    fn update(&mut self, data: &[u8]);
+
+    /// Returns the checksum of the data fed so far.
+    fn finish(&self) -> Self::Output;
+}
+
+/// Reader that feeds the bytes read to a checksum algorithm.
+/// 
+/// This is used by `checksummed`, but can also be used
+/// to checksum any part of a stream.
+/// 
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::checksum::{ HashReader, Sum8 };
+/// use bin_io::numbers::be_u16;
+/// use bin_io::read;
+/// 
+/// let vec = vec![ 0x01, 0x02 ];
+/// let mut r = HashReader::new(Cursor::new(vec), Sum8::default());
+/// 
+/// let val = read(&mut r, be_u16())
+///     .unwrap();
+/// 
+/// assert_eq!(val, 0x0102);
+/// assert_eq!(r.checksum(), 0x03);
+/// ```
+#[derive(Clone, Debug)]
+pub struct HashReader<R, A> {
+    inner: R,
+    a: A
+}
+
+impl<R, A: Checksum> HashReader<R, A> {
+    pub fn new(inner: R, a: A) -> Self {
+        Self { inner, a }
+    }
+
+    /// Returns the checksum of the bytes read so far.
+    pub fn checksum(&self) -> A::Output {
+        self.a.finish()
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read, A: Checksum> Read for HashReader<R, A> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.a.update(&buf[..n]);
+        Ok(n)
+    }
+}
+
+/// Writer that feeds the bytes written to a checksum algorithm.
+/// 
+/// This is used by `checksummed`, see `HashReader`.
+#[derive(Clone, Debug)]
+pub struct HashWriter<W, A> {
+    inner: W,
+    a: A
+}
+
+impl<W, A: Checksum> HashWriter<W, A> {
+    pub fn new(inner: W, a: A) -> Self {
+        Self { inner, a }
+    }
+
+    /// Returns the checksum of the bytes written so far.
+    pub fn checksum(&self) -> A::Output {
+        self.a.finish()
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write, A: Checksum> Write for HashWriter<W, A> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.a.update(&buf[..n]);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Reads/Writes a value protected by a checksum.
+/// 
+/// `f` is the parser of the protected value, `c` the parser 
+/// of the checksum and `a` the `Checksum` algorithm.
+/// 
+/// ## Reading
+/// Reads the value, hashing the bytes as they're read. Then 
+/// reads the checksum and compares it with the computed one.
+/// 
+/// ## Writing
+/// Writes the value, hashing the bytes as they're written. 
+/// Then writes the computed checksum.
+/// 
+/// # Remarks
+/// The checksum is computed through `HashReader` and 
+/// `HashWriter`, so the stream doesn't have to implement 
+/// `Seek`. Since these borrow the stream only for the 
+/// duration of a call, `f` must work with any reader/writer:
+/// its closures take `&mut dyn Read` and `&mut dyn Write`.
+/// The `checksummed!` macro builds them from a parser.
+/// 
+/// # Errors
+/// Reading fails with `BinError::ChecksumFail` if the
+/// checksums don't match.
+/// 
+/// # Examples
+/// ```
+/// use std::io::{ Cursor, Read, Write };
+/// use bin_io::checksum::{ checksummed, Sum8 };
+/// use bin_io::numbers::{ be_u8, be_u16 };
+/// use bin_io::{ ReadFn, WriteFn, read, write, fixed_size };
+/// 
+/// fn parser<R: Read + ?Sized, W: Write + ?Sized>()
+/// -> (impl ReadFn<R, u16>, impl WriteFn<W, u16>) {
+///     checksummed(
+///         (|r: &mut dyn Read| read(r, be_u16()), 
+///         |w: &mut dyn Write, v: &u16| write(w, v, be_u16())),
+///         be_u8(), 
+///         Sum8::default()
+///     )
+/// }
+/// 
+/// let vec = Vec::new();
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// write(&mut cursor, &0x0102, parser())
+///     .unwrap();
+/// 
+/// assert_eq!(cursor.get_ref(), &vec![ 0x01, 0x02, 0x03 ]);
+/// 
+/// cursor.set_position(0);
+/// 
+/// let value = read(&mut cursor, parser())
+///     .unwrap();
+/// 
+/// assert_eq!(value, 0x0102);
+/// assert_eq!(fixed_size(parser()), Some(3));
+/// ```
+pub fn checksummed<R, W, Rf, Wf, Rc, Wc, I, A>(f: (Rf, Wf), c: (Rc, Wc), a: A)
+-> (impl ReadFn<R, I>, impl WriteFn<W, I>)
+where R: Read + ?Sized, W: Write + ?Sized, 
+      Rf: Fn(&mut dyn Read) -> io::Result<I>, Wf: Fn(&mut dyn Write, &I) -> io::Result<()>,
+      Rc: ReadFn<R, A::Output>, Wc: WriteFn<W, A::Output>,
+      A: Checksum + Clone, A::Output: PartialEq {
+
+    let (rf, wf) = f;
+    let (rc, wc) = c;
+    let wa = a.clone();
+
+    (move |r: &mut R| {
+        let mut h = HashReader::new(&mut *r, a.clone());
+        let i = rf(&mut h)?;
+        let sum = h.checksum();
+
+        // While measuring there are no bytes to hash
+        match rc(r)? == sum || size::querying() {
+            true => Ok(i),
+            false => Err(Error::from(BinError::ChecksumFail))
+        }
+    },
+    move |w: &mut W, i: &I| {
+        let mut h = HashWriter::new(&mut *w, wa.clone());
+        wf(&mut h, i)?;
+        let sum = h.checksum();
+
+        wc(w, &sum)
+    })
+}
+
+const fn crc32_table(poly: u32) -> [u32; 256] {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut c = i as u32;
+        let mut j = 0;
+        while j < 8 {
+            c = match c & 1 {
+                1 => poly ^ (c >> 1),
+                _ => c >> 1
+            };
+            j += 1;
+        }
+
+        table[i] = c;
+        i += 1;
+    }
+
+    table
+}
+
+const fn crc16_table(poly: u16) -> [u16; 256] {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut c = (i as u16) << 8;
+        let mut j = 0;
+        while j < 8 {
+            c = match c & 0x8000 {
+                0 => c << 1,
+                _ => (c << 1) ^ poly
+            };
+            j += 1;
+        }
+
+        table[i] = c;
+        i += 1;
+    }
+
+    table
+}
+
+static CRC32_IEEE: [u32; 256] = crc32_table(0xedb8_8320);
+static CRC32_CASTAGNOLI: [u32; 256] = crc32_table(0x82f6_3b78);
+static CRC16_CCITT: [u16; 256] = crc16_table(0x1021);
+
+/// CRC-32 checksum.
+/// 
+/// # Examples
+/// ```
+/// use bin_io::checksum::{ Checksum, Crc32 };
+/// 
+/// let mut a = Crc32::ieee();
+/// a.update(b"123456789");
+/// assert_eq!(a.finish(), 0xcbf43926);
+/// 
+/// let mut a = Crc32::castagnoli();
+/// a.update(b"123456789");
+/// assert_eq!(a.finish(), 0xe3069283);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Crc32 {
+    table: &'static [u32; 256],
+    crc: u32
+}
+
+impl Crc32 {
+    /// CRC-32 with the IEEE polynomial (used by zip, png, ethernet...).
+    pub fn ieee() -> Self {
+        Self { table: &CRC32_IEEE, crc: 0xffff_ffff }
+    }
+
+    /// CRC-32C with the Castagnoli polynomial (used by iSCSI, ext4, btrfs...).
+    pub fn castagnoli() -> Self {
+        Self { table: &CRC32_CASTAGNOLI, crc: 0xffff_ffff }
+    }
+}
+
+impl Default for Crc32 {
+    fn default() -> Self {
+        Self::ieee()
+    }
+}
+
+impl Checksum for Crc32 {
+    type Output = u32;
+
+    fn update(&mut self, data: &[u8]) {
+        for b in data {
+            self.crc = self.table[((self.crc ^ *b as u32) & 0xff) as usize] ^ (self.crc >> 8);
+        }
+    }
+
+    fn finish(&self) -> u32 {
+        !self.crc
+    }
+}
+
+/// CRC-16/CCITT checksum (also known as CRC-16/CCITT-FALSE).
+/// 
+/// # Examples
+/// ```
+/// use bin_io::checksum::{ Checksum, Crc16Ccitt };
+/// 
+/// let mut a = Crc16Ccitt::default();
+/// a.update(b"123456789");
+/// assert_eq!(a.finish(), 0x29b1);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Crc16Ccitt {
+    crc: u16
+}
+
+impl Default for Crc16Ccitt {
+    fn default() -> Self {
+        Self { crc: 0xffff }
+    }
+}
+
+impl Checksum for Crc16Ccitt {
+    type Output = u16;
+
+    fn update(&mut self, data: &[u8]) {
+        for b in data {
+            self.crc = (self.crc << 8) ^ CRC16_CCITT[((self.crc >> 8) ^ *b as u16) as usize & 0xff];
+        }
+    }
+
+    fn finish(&self) -> u16 {
+        self.crc
+    }
+}
+
+/// Adler-32 checksum.
+/// 
+/// # Examples
+/// ```
+/// use bin_io::checksum::{ Checksum, Adler32 };
+/// 
+/// let mut a = Adler32::default();
+/// a.update(b"Wikipedia");
+/// assert_eq!(a.finish(), 0x11e60398);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Adler32 {
+    a: u32,
+    b: u32
+}
+
+impl Default for Adler32 {
+    fn default() -> Self {
+        Self { a: 1, b: 0 }
+    }
+}
+
+impl Checksum for Adler32 {
+    type Output = u32;
+
+    fn update(&mut self, data: &[u8]) {
+        for b in data {
+            self.a = (self.a + *b as u32) % 65521;
+            self.b = (self.b + self.a) % 65521;
+        }
+    }
+
+    fn finish(&self) -> u32 {
+        (self.b << 16) | self.a
+    }
+}
+
+/// Additive checksum, the wrapping sum of all the bytes.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Sum8 {
+    sum: u8
+}
+
+impl Checksum for Sum8 {
+    type Output = u8;
+
+    fn update(&mut self, data: &[u8]) {
+        for b in data {
+            self.sum = self.sum.wrapping_add(*b);
+        }
+    }
+
+    fn finish(&self) -> u8 {
+        self.sum
+    }
+}
+
+/// Xor checksum, the xor of all the bytes.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Xor8 {
+    xor: u8
+}
+
+impl Checksum for Xor8 {
+    type Output = u8;
+
+    fn update(&mut self, data: &[u8]) {
+        for b in data {
+            self.xor ^= *b;
+        }
+    }
+
+    fn finish(&self) -> u8 {
+        self.xor
+    }
+}
@@ -0,0 +1,670 @@
+//! Contains parsers for the standard collections and smart pointers.
+//!
+//! # Examples
+//! ```
+//! use std::io::Cursor;
+//! use std::collections::HashMap;
+//! use bin_io::collections::{ prefixed_hash_map, Duplicates };
+//! use bin_io::numbers::{ be_u8, be_u16 };
+//! use bin_io::strings::null_utf8_max;
+//! use bin_io::{ read, write };
+//!
+//! let mut map = HashMap::new();
+//! map.insert("b".to_owned(), 2);
+//! map.insert("a".to_owned(), 1);
+//!
+//! let vec = Vec::new();
+//! let mut cursor = Cursor::new(vec);
+//!
+//! write(&mut cursor, &map, prefixed_hash_map(be_u8(), null_utf8_max(16), be_u16(), Duplicates::Error))
+//!     .unwrap();
+//!
+//! // Entries are always written ordered by key
+//! assert_eq!(cursor.get_ref(), &[
+//!     0x02,
+//!     b'a', 0x00, 0x00, 0x01,
+//!     b'b', 0x00, 0x00, 0x02
+//! ]);
+//!
+//! cursor.set_position(0);
+//!
+//! let other = read(&mut cursor, prefixed_hash_map(be_u8(), null_utf8_max(16), be_u16(), Duplicates::Error))
+//!     .unwrap();
+//!
+//! assert_eq!(other, map);
+//! ```
+
+use crate::{ WriteFn, ReadFn, BinError, try_cast, MAX_PREALLOC };
+use std::io::{ self, Read, Write, Error };
+use std::collections::{ HashMap, BTreeMap, HashSet, BTreeSet, VecDeque };
+use std::convert::{ TryFrom, TryInto };
+use std::hash::Hash;
+use std::rc::Rc;
+use std::sync::Arc;
+
+/// What to do when a key is read more than once.
+///
+/// # Remarks
+/// With `KeepFirst` and `KeepLast` the repeated entries 
+/// are dropped, so writing the collection back produces 
+/// fewer entries (and a shorter length prefix) than the 
+/// ones that were read: the output doesn't round-trip 
+/// byte-for-byte.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Duplicates {
+    /// Fail with an error.
+    Error,
+    /// Keep the first value read.
+    KeepFirst,
+    /// Keep the last value read.
+    KeepLast
+}
+
+/// Collection that can be filled with key/value pairs.
+trait Entries<K, V>: Default {
+    fn contains(&self, k: &K) -> bool;
+    fn put(&mut self, k: K, v: V);
+}
+
+impl<K: Eq + Hash, V> Entries<K, V> for HashMap<K, V> {
+    fn contains(&self, k: &K) -> bool {
+        self.contains_key(k)
+    }
+
+    fn put(&mut self, k: K, v: V) {
+        self.insert(k, v);
+    }
+}
+
+impl<K: Ord, V> Entries<K, V> for BTreeMap<K, V> {
+    fn contains(&self, k: &K) -> bool {
+        self.contains_key(k)
+    }
+
+    fn put(&mut self, k: K, v: V) {
+        self.insert(k, v);
+    }
+}
+
+impl<K: Eq + Hash> Entries<K, ()> for HashSet<K> {
+    fn contains(&self, k: &K) -> bool {
+        HashSet::contains(self, k)
+    }
+
+    fn put(&mut self, k: K, _: ()) {
+        self.replace(k);
+    }
+}
+
+impl<K: Ord> Entries<K, ()> for BTreeSet<K> {
+    fn contains(&self, k: &K) -> bool {
+        BTreeSet::contains(self, k)
+    }
+
+    fn put(&mut self, k: K, _: ()) {
+        self.replace(k);
+    }
+}
+
+fn read_entries<R, C, K, V>(r: &mut R, mut f: impl FnMut(&mut R) -> io::Result<(K, V)>,
+    n: usize, dup: Duplicates) -> io::Result<C>
+where R: Read + ?Sized, C: Entries<K, V> {
+
+    let mut c = C::default();
+
+    for _ in 0..n {
+        let (k, v) = f(r)?;
+
+        match (c.contains(&k), dup) {
+            (false, _) | (true, Duplicates::KeepLast) => c.put(k, v),
+            (true, Duplicates::KeepFirst) => {},
+            (true, Duplicates::Error) => return Err(Error::from(BinError::DuplicateKey))
+        }
+    }
+
+    Ok(c)
+}
+
+fn check_len(len: usize, n: usize) -> io::Result<()> {
+    match len == n {
+        true => Ok(()),
+        false => Err(Error::from(BinError::LengthMismatch))
+    }
+}
+
+/// Encodes the keys of a hashed collection and sorts 
+/// the entries by the encoded keys, so that the output 
+/// is deterministic without requiring `Ord`.
+fn sorted<'a, K: 'a, V>(entries: impl Iterator<Item = (&'a K, V)>, 
+    kw: &impl WriteFn<Vec<u8>, K>) -> io::Result<Vec<(Vec<u8>, V)>> {
+
+    let mut entries = entries
+        .map(|(k, v)| {
+            let mut b = Vec::new();
+            kw(&mut b, k)?;
+            Ok((b, v))
+        })
+        .collect::<io::Result<Vec<_>>>()?;
+
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(entries)
+}
+
+fn read_queue<R, I>(r: &mut R, rf: &impl ReadFn<R, I>, n: usize) -> io::Result<VecDeque<I>>
+where R: Read + ?Sized {
+
+    let mut queue = VecDeque::with_capacity(n.min(MAX_PREALLOC / std::mem::size_of::<I>().max(1)));
+
+    for _ in 0..n {
+        queue.push_back(rf(r)?);
+    }
+
+    Ok(queue)
+}
+
+/// Reads/Writes a `HashMap` of `n` entries, each made
+/// of a key followed by its value.
+///
+/// ## Reading
+/// The function reads `n` key/value pairs, handling
+/// repeated keys according to `dup`.
+///
+/// ## Writing
+/// The function writes the entries ordered by the bytes 
+/// of their keys, so that the output is always the same.
+///
+/// # Errors
+/// When writing, if the length of the map is 
+/// not `n` an error is returned.
+///
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::collections::{ hash_map, Duplicates };
+/// use bin_io::numbers::be_u8;
+/// use bin_io::read;
+///
+/// let vec = vec![ 0x01, 0x10, 0x01, 0x20 ];
+/// let mut cursor = Cursor::new(vec);
+///
+/// let map = read(&mut cursor, hash_map(be_u8(), be_u8(), 2, Duplicates::KeepLast))
+///     .unwrap();
+///
+/// assert_eq!(map.len(), 1);
+/// assert_eq!(map[&0x01], 0x20);
+///
+/// cursor.set_position(0);
+///
+/// let err = read(&mut cursor, hash_map(be_u8(), be_u8(), 2, Duplicates::Error));
+///
+/// assert!(err.is_err());
+/// ```
+pub fn hash_map<R, W, Kr, Kw, Vr, Vw, K, V>(key: (Kr, Kw), value: (Vr, Vw), n: usize, dup: Duplicates)
+-> (impl ReadFn<R, HashMap<K, V>>, impl WriteFn<W, HashMap<K, V>>)
+where R: Read + ?Sized, W: Write + ?Sized,
+    Kr: ReadFn<R, K>, Kw: WriteFn<Vec<u8>, K>, Vr: ReadFn<R, V>, Vw: WriteFn<W, V>, K: Eq + Hash {
+
+    let (kr, kw) = key;
+    let (vr, vw) = value;
+
+    (move |r: &mut R| {
+        read_entries(r, |r| Ok((kr(r)?, vr(r)?)), n, dup)
+    },
+    move |w: &mut W, m: &HashMap<K, V>| {
+        check_len(m.len(), n)?;
+
+        for (k, v) in sorted(m.iter(), &kw)? {
+            w.write_all(&k[..])?;
+            vw(w, v)?;
+        }
+
+        Ok(())
+    })
+}
+
+/// Reads/Writes a `HashMap` prefixed by its length,
+/// read/written using the passed parser.
+///
+/// See `hash_map` for details.
+pub fn prefixed_hash_map<R, W, Lr, Lw, L, Kr, Kw, Vr, Vw, K, V>(len: (Lr, Lw), key: (Kr, Kw),
+    value: (Vr, Vw), dup: Duplicates)
+-> (impl ReadFn<R, HashMap<K, V>>, impl WriteFn<W, HashMap<K, V>>)
+where R: Read + ?Sized, W: Write + ?Sized,
+    Lr: ReadFn<R, L>, Lw: WriteFn<W, L>, L: TryFrom<usize> + TryInto<usize>,
+    Kr: ReadFn<R, K>, Kw: WriteFn<Vec<u8>, K>, Vr: ReadFn<R, V>, Vw: WriteFn<W, V>, K: Eq + Hash {
+
+    let (lr, lw) = try_cast(len);
+    let (kr, kw) = key;
+    let (vr, vw) = value;
+
+    (move |r: &mut R| {
+        let n = lr(r)?;
+        read_entries(r, |r| Ok((kr(r)?, vr(r)?)), n, dup)
+    },
+    move |w: &mut W, m: &HashMap<K, V>| {
+        lw(w, &m.len())?;
+
+        for (k, v) in sorted(m.iter(), &kw)? {
+            w.write_all(&k[..])?;
+            vw(w, v)?;
+        }
+
+        Ok(())
+    })
+}
+
+/// Reads/Writes a `BTreeMap` of `n` entries, each made
+/// of a key followed by its value.
+///
+/// ## Reading
+/// The function reads `n` key/value pairs, handling
+/// repeated keys according to `dup`.
+///
+/// ## Writing
+/// The function writes the entries ordered by key.
+///
+/// # Errors
+/// When writing, if the length of the map is 
+/// not `n` an error is returned.
+///
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use std::collections::BTreeMap;
+/// use bin_io::collections::{ btree_map, Duplicates };
+/// use bin_io::numbers::{ be_u8, le_u16 };
+/// use bin_io::write;
+///
+/// let vec = Vec::new();
+/// let mut cursor = Cursor::new(vec);
+///
+/// let mut map = BTreeMap::new();
+/// map.insert(0x02, 0x20);
+/// map.insert(0x01, 0x10);
+///
+/// write(&mut cursor, &map, btree_map(be_u8(), le_u16(), 2, Duplicates::Error))
+///     .unwrap();
+///
+/// assert_eq!(cursor.get_ref(), &[ 0x01, 0x10, 0x00, 0x02, 0x20, 0x00 ]);
+/// ```
+pub fn btree_map<R, W, Kr, Kw, Vr, Vw, K, V>(key: (Kr, Kw), value: (Vr, Vw), n: usize, dup: Duplicates)
+-> (impl ReadFn<R, BTreeMap<K, V>>, impl WriteFn<W, BTreeMap<K, V>>)
+where R: Read + ?Sized, W: Write + ?Sized,
+    Kr: ReadFn<R, K>, Kw: WriteFn<W, K>, Vr: ReadFn<R, V>, Vw: WriteFn<W, V>, K: Ord {
+
+    let (kr, kw) = key;
+    let (vr, vw) = value;
+
+    (move |r: &mut R| {
+        read_entries(r, |r| Ok((kr(r)?, vr(r)?)), n, dup)
+    },
+    move |w: &mut W, m: &BTreeMap<K, V>| {
+        check_len(m.len(), n)?;
+
+        for (k, v) in m {
+            kw(w, k)?;
+            vw(w, v)?;
+        }
+
+        Ok(())
+    })
+}
+
+/// Reads/Writes a `BTreeMap` prefixed by its length,
+/// read/written using the passed parser.
+///
+/// See `btree_map` for details.
+pub fn prefixed_btree_map<R, W, Lr, Lw, L, Kr, Kw, Vr, Vw, K, V>(len: (Lr, Lw), key: (Kr, Kw),
+    value: (Vr, Vw), dup: Duplicates)
+-> (impl ReadFn<R, BTreeMap<K, V>>, impl WriteFn<W, BTreeMap<K, V>>)
+where R: Read + ?Sized, W: Write + ?Sized,
+    Lr: ReadFn<R, L>, Lw: WriteFn<W, L>, L: TryFrom<usize> + TryInto<usize>,
+    Kr: ReadFn<R, K>, Kw: WriteFn<W, K>, Vr: ReadFn<R, V>, Vw: WriteFn<W, V>, K: Ord {
+
+    let (lr, lw) = try_cast(len);
+    let (kr, kw) = key;
+    let (vr, vw) = value;
+
+    (move |r: &mut R| {
+        let n = lr(r)?;
+        read_entries(r, |r| Ok((kr(r)?, vr(r)?)), n, dup)
+    },
+    move |w: &mut W, m: &BTreeMap<K, V>| {
+        lw(w, &m.len())?;
+
+        for (k, v) in m {
+            kw(w, k)?;
+            vw(w, v)?;
+        }
+
+        Ok(())
+    })
+}
+
+/// Reads/Writes a `HashSet` of `n` values.
+///
+/// ## Reading
+/// The function reads `n` values, handling repeated
+/// values according to `dup`.
+///
+/// ## Writing
+/// The function writes the values ordered by their 
+/// bytes, so that the output is always the same.
+///
+/// # Errors
+/// When writing, if the length of the set is 
+/// not `n` an error is returned.
+///
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use std::collections::HashSet;
+/// use bin_io::collections::{ hash_set, Duplicates };
+/// use bin_io::numbers::be_u8;
+/// use bin_io::write;
+///
+/// let vec = Vec::new();
+/// let mut cursor = Cursor::new(vec);
+///
+/// let set: HashSet<u8> = vec![ 0x30, 0x10, 0x20 ].into_iter().collect();
+///
+/// write(&mut cursor, &set, hash_set(be_u8(), 3, Duplicates::Error))
+///     .unwrap();
+///
+/// assert_eq!(cursor.get_ref(), &[ 0x10, 0x20, 0x30 ]);
+/// ```
+pub fn hash_set<R, W, Rf, Wf, I>(f: (Rf, Wf), n: usize, dup: Duplicates)
+-> (impl ReadFn<R, HashSet<I>>, impl WriteFn<W, HashSet<I>>)
+where R: Read + ?Sized, W: Write + ?Sized, Rf: ReadFn<R, I>, Wf: WriteFn<Vec<u8>, I>, I: Eq + Hash {
+
+    let (rf, wf) = f;
+
+    (move |r: &mut R| {
+        read_entries(r, |r| Ok((rf(r)?, ())), n, dup)
+    },
+    move |w: &mut W, s: &HashSet<I>| {
+        check_len(s.len(), n)?;
+
+        for (v, _) in sorted(s.iter().map(|v| (v, ())), &wf)? {
+            w.write_all(&v[..])?;
+        }
+
+        Ok(())
+    })
+}
+
+/// Reads/Writes a `HashSet` prefixed by its length,
+/// read/written using the passed parser.
+///
+/// See `hash_set` for details.
+pub fn prefixed_hash_set<R, W, Lr, Lw, L, Rf, Wf, I>(len: (Lr, Lw), f: (Rf, Wf), dup: Duplicates)
+-> (impl ReadFn<R, HashSet<I>>, impl WriteFn<W, HashSet<I>>)
+where R: Read + ?Sized, W: Write + ?Sized,
+    Lr: ReadFn<R, L>, Lw: WriteFn<W, L>, L: TryFrom<usize> + TryInto<usize>,
+    Rf: ReadFn<R, I>, Wf: WriteFn<Vec<u8>, I>, I: Eq + Hash {
+
+    let (lr, lw) = try_cast(len);
+    let (rf, wf) = f;
+
+    (move |r: &mut R| {
+        let n = lr(r)?;
+        read_entries(r, |r| Ok((rf(r)?, ())), n, dup)
+    },
+    move |w: &mut W, s: &HashSet<I>| {
+        lw(w, &s.len())?;
+
+        for (v, _) in sorted(s.iter().map(|v| (v, ())), &wf)? {
+            w.write_all(&v[..])?;
+        }
+
+        Ok(())
+    })
+}
+
+/// Reads/Writes a `BTreeSet` of `n` values.
+///
+/// ## Reading
+/// The function reads `n` values, handling repeated
+/// values according to `dup`.
+///
+/// ## Writing
+/// The function writes the values in order.
+///
+/// # Errors
+/// When writing, if the length of the set is 
+/// not `n` an error is returned.
+///
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::collections::{ btree_set, Duplicates };
+/// use bin_io::numbers::be_u8;
+/// use bin_io::read;
+///
+/// let vec = vec![ 0x20, 0x10, 0x20 ];
+/// let mut cursor = Cursor::new(vec);
+///
+/// let set = read(&mut cursor, btree_set(be_u8(), 3, Duplicates::KeepFirst))
+///     .unwrap();
+///
+/// assert_eq!(set.into_iter().collect::<Vec<_>>(), vec![ 0x10, 0x20 ]);
+/// ```
+pub fn btree_set<R, W, Rf, Wf, I>(f: (Rf, Wf), n: usize, dup: Duplicates)
+-> (impl ReadFn<R, BTreeSet<I>>, impl WriteFn<W, BTreeSet<I>>)
+where R: Read + ?Sized, W: Write + ?Sized, Rf: ReadFn<R, I>, Wf: WriteFn<W, I>, I: Ord {
+
+    let (rf, wf) = f;
+
+    (move |r: &mut R| {
+        read_entries(r, |r| Ok((rf(r)?, ())), n, dup)
+    },
+    move |w: &mut W, s: &BTreeSet<I>| {
+        check_len(s.len(), n)?;
+
+        for v in s {
+            wf(w, v)?;
+        }
+
+        Ok(())
+    })
+}
+
+/// Reads/Writes a `BTreeSet` prefixed by its length,
+/// read/written using the passed parser.
+///
+/// See `btree_set` for details.
+pub fn prefixed_btree_set<R, W, Lr, Lw, L, Rf, Wf, I>(len: (Lr, Lw), f: (Rf, Wf), dup: Duplicates)
+-> (impl ReadFn<R, BTreeSet<I>>, impl WriteFn<W, BTreeSet<I>>)
+where R: Read + ?Sized, W: Write + ?Sized,
+    Lr: ReadFn<R, L>, Lw: WriteFn<W, L>, L: TryFrom<usize> + TryInto<usize>,
+    Rf: ReadFn<R, I>, Wf: WriteFn<W, I>, I: Ord {
+
+    let (lr, lw) = try_cast(len);
+    let (rf, wf) = f;
+
+    (move |r: &mut R| {
+        let n = lr(r)?;
+        read_entries(r, |r| Ok((rf(r)?, ())), n, dup)
+    },
+    move |w: &mut W, s: &BTreeSet<I>| {
+        lw(w, &s.len())?;
+
+        for v in s {
+            wf(w, v)?;
+        }
+
+        Ok(())
+    })
+}
+
+/// Reads/Writes a `VecDeque` of `n` values.
+///
+/// This is the `VecDeque` variant of `count`.
+///
+/// # Errors
+/// When writing, if the length of the queue is 
+/// not `n` an error is returned.
+///
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::collections::vec_deque;
+/// use bin_io::numbers::be_u8;
+/// use bin_io::{ read, write };
+///
+/// let vec = vec![ 0x10, 0x20 ];
+/// let mut cursor = Cursor::new(vec);
+///
+/// let mut queue = read(&mut cursor, vec_deque(be_u8(), 2))
+///     .unwrap();
+///
+/// assert_eq!(queue.pop_front(), Some(0x10));
+///
+/// // One value is missing
+/// let err = write(&mut cursor, &queue, vec_deque(be_u8(), 2));
+///
+/// assert!(err.is_err());
+/// ```
+pub fn vec_deque<R, W, Rf, Wf, I>(f: (Rf, Wf), n: usize)
+-> (impl ReadFn<R, VecDeque<I>>, impl WriteFn<W, VecDeque<I>>)
+where R: Read + ?Sized, W: Write + ?Sized, Rf: ReadFn<R, I>, Wf: WriteFn<W, I> {
+
+    let (rf, wf) = f;
+
+    (move |r: &mut R| {
+        read_queue(r, &rf, n)
+    },
+    move |w: &mut W, q: &VecDeque<I>| {
+        check_len(q.len(), n)?;
+
+        for v in q {
+            wf(w, v)?;
+        }
+
+        Ok(())
+    })
+}
+
+/// Reads/Writes a `VecDeque` prefixed by its length,
+/// read/written using the passed parser.
+///
+/// See `vec_deque` for details.
+pub fn prefixed_vec_deque<R, W, Lr, Lw, L, Rf, Wf, I>(len: (Lr, Lw), f: (Rf, Wf))
+-> (impl ReadFn<R, VecDeque<I>>, impl WriteFn<W, VecDeque<I>>)
+where R: Read + ?Sized, W: Write + ?Sized,
+    Lr: ReadFn<R, L>, Lw: WriteFn<W, L>, L: TryFrom<usize> + TryInto<usize>,
+    Rf: ReadFn<R, I>, Wf: WriteFn<W, I> {
+
+    let (lr, lw) = try_cast(len);
+    let (rf, wf) = f;
+
+    (move |r: &mut R| {
+        let n = lr(r)?;
+        read_queue(r, &rf, n)
+    },
+    move |w: &mut W, q: &VecDeque<I>| {
+        lw(w, &q.len())?;
+
+        for v in q {
+            wf(w, v)?;
+        }
+
+        Ok(())
+    })
+}
+
+/// Reads/Writes a value inside a `Box`.
+///
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::collections::boxed_value;
+/// use bin_io::numbers::be_u8;
+/// use bin_io::read;
+///
+/// let vec = vec![ 0x10 ];
+/// let mut cursor = Cursor::new(vec);
+///
+/// let val = read(&mut cursor, boxed_value(be_u8()))
+///     .unwrap();
+///
+/// assert_eq!(val, Box::new(0x10));
+/// ```
+#[allow(clippy::borrowed_box)]
+pub fn boxed_value<R: Read + ?Sized, W: Write + ?Sized, Rf, Wf, I>(f: (Rf, Wf))
+-> (impl ReadFn<R, Box<I>>, impl WriteFn<W, Box<I>>)
+where Rf: ReadFn<R, I>, Wf: WriteFn<W, I> {
+
+    let (rf, wf) = f;
+
+    (move |r: &mut R| {
+        Ok(Box::new(rf(r)?))
+    },
+    move |w: &mut W, i: &Box<I>| {
+        wf(w, i)
+    })
+}
+
+/// Reads/Writes a value inside an `Rc`.
+///
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use std::rc::Rc;
+/// use bin_io::collections::rc;
+/// use bin_io::strings::null_utf8_max;
+/// use bin_io::write;
+///
+/// let vec = Vec::new();
+/// let mut cursor = Cursor::new(vec);
+///
+/// write(&mut cursor, &Rc::new("Hi".to_owned()), rc(null_utf8_max(16)))
+///     .unwrap();
+///
+/// assert_eq!(cursor.get_ref(), b"Hi\0");
+/// ```
+pub fn rc<R: Read + ?Sized, W: Write + ?Sized, Rf, Wf, I>(f: (Rf, Wf))
+-> (impl ReadFn<R, Rc<I>>, impl WriteFn<W, Rc<I>>)
+where Rf: ReadFn<R, I>, Wf: WriteFn<W, I> {
+
+    let (rf, wf) = f;
+
+    (move |r: &mut R| {
+        Ok(Rc::new(rf(r)?))
+    },
+    move |w: &mut W, i: &Rc<I>| {
+        wf(w, i)
+    })
+}
+
+/// Reads/Writes a value inside an `Arc`.
+///
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use std::sync::Arc;
+/// use bin_io::collections::arc;
+/// use bin_io::numbers::le_u32;
+/// use bin_io::read;
+///
+/// let vec = vec![ 0x01, 0x00, 0x00, 0x00 ];
+/// let mut cursor = Cursor::new(vec);
+///
+/// let val = read(&mut cursor, arc(le_u32()))
+///     .unwrap();
+///
+/// assert_eq!(val, Arc::new(1));
+/// ```
+pub fn arc<R: Read + ?Sized, W: Write + ?Sized, Rf, Wf, I>(f: (Rf, Wf))
+-> (impl ReadFn<R, Arc<I>>, impl WriteFn<W, Arc<I>>)
+where Rf: ReadFn<R, I>, Wf: WriteFn<W, I> {
+
+    let (rf, wf) = f;
+
+    (move |r: &mut R| {
+        Ok(Arc::new(rf(r)?))
+    },
+    move |w: &mut W, i: &Arc<I>| {
+        wf(w, i)
+    })
+}
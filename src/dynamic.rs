@@ -0,0 +1,424 @@
+//! Contains type erased parsers.
+//!
+//! Every parser has its own opaque type, so a parser can't
+//! refer to itself. `boxed!` erases the type of a parser,
+//! and `lazy` builds it only when it's used, which makes
+//! recursive definitions possible.
+//!
+//! # Examples
+//! ```
+//! use std::io::Cursor;
+//! use bin_io::dynamic::{ lazy, DynParser };
+//! use bin_io::numbers::{ be_u8, be_u32 };
+//! use bin_io::{ seq, boxed, count, read, write };
+//!
+//! #[derive(Debug, PartialEq, Eq)]
+//! struct Node {
+//!     value: u32,
+//!     children: Vec<Node>
+//! }
+//!
+//! fn node() -> DynParser<Node> {
+//!     boxed!(seq!(
+//!         Node { value, children },
+//!         value: be_u32() =>
+//!         len: be_u8(), children.len() as _ =>
+//!         children: count(lazy(node), len as usize) =>
+//!     ))
+//! }
+//!
+//! let tree = Node {
+//!     value: 1,
+//!     children: vec![
+//!         Node { value: 2, children: vec![] },
+//!         Node { value: 3, children: vec![ Node { value: 4, children: vec![] } ] }
+//!     ]
+//! };
+//!
+//! let vec = Vec::new();
+//! let mut cursor = Cursor::new(vec);
+//!
+//! write(&mut cursor, &tree, lazy(node))
+//!     .unwrap();
+//!
+//! cursor.set_position(0);
+//!
+//! let other = read(&mut cursor, lazy(node))
+//!     .unwrap();
+//!
+//! assert_eq!(other, tree);
+//! ```
+
+use crate::{ WriteFn, ReadFn, BinError, size };
+use std::io::{ self, Read, Write, Error };
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::cell::{ Cell, OnceCell };
+use std::rc::Rc;
+
+/// Boxed read closure, reading from any reader.
+pub type BoxReadFn<'a, I> = Box<dyn for<'r> Fn(&mut (dyn Read + 'r)) -> io::Result<I> + 'a>;
+
+/// Boxed write closure, writing to any writer.
+pub type BoxWriteFn<'a, I> = Box<dyn for<'r> Fn(&mut (dyn Write + 'r), &I) -> io::Result<()> + 'a>;
+
+/// Type erased read/write tuple.
+/// 
+/// `'a` is the lifetime of the variables captured
+/// by the parser, not the one of the stream.
+pub type BoxParser<'a, I> = (BoxReadFn<'a, I>, BoxWriteFn<'a, I>);
+
+/// Type erased read/write tuple, that doesn't borrow 
+/// anything, so it can be stored freely, for example 
+/// in a `Registry`.
+pub type DynParser<I> = BoxParser<'static, I>;
+
+/// Default recursion limit used by `lazy`.
+pub const DEFAULT_MAX_DEPTH: usize = 128;
+
+/// Conversion of a reader into a trait object.
+///
+/// This is implemented both for sized readers and for
+/// `dyn Read` itself, so that `lazy` can be used both
+/// at the top level and inside a boxed parser.
+pub trait AsDynRead {
+    fn as_dyn_read(&mut self) -> &mut dyn Read;
+}
+
+impl<R: Read> AsDynRead for R {
+    fn as_dyn_read(&mut self) -> &mut dyn Read {
+        self
+    }
+}
+
+impl<'a> AsDynRead for dyn Read + 'a {
+    fn as_dyn_read(&mut self) -> &mut dyn Read {
+        self
+    }
+}
+
+/// Conversion of a writer into a trait object.
+///
+/// See `AsDynRead`.
+pub trait AsDynWrite {
+    fn as_dyn_write(&mut self) -> &mut dyn Write;
+}
+
+impl<W: Write> AsDynWrite for W {
+    fn as_dyn_write(&mut self) -> &mut dyn Write {
+        self
+    }
+}
+
+impl<'a> AsDynWrite for dyn Write + 'a {
+    fn as_dyn_write(&mut self) -> &mut dyn Write {
+        self
+    }
+}
+
+/// Marker trait used to capture lifetimes 
+/// in the `impl Trait` returned by `dispatch`.
+#[doc(hidden)]
+pub trait Captures<'r> { }
+impl<'r, T: ?Sized> Captures<'r> for T { }
+
+thread_local! {
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Keeps track of the nesting of `lazy` parsers
+/// in the current thread.
+struct DepthGuard;
+
+impl DepthGuard {
+    fn enter(max: usize) -> io::Result<Self> {
+        DEPTH.with(|d| match d.get() < max {
+            true => {
+                d.set(d.get() + 1);
+                Ok(DepthGuard)
+            },
+            false => Err(Error::from(BinError::RecursionLimit(max)))
+        })
+    }
+}
+
+impl Drop for DepthGuard {
+    fn drop(&mut self) {
+        DEPTH.with(|d| d.set(d.get() - 1));
+    }
+}
+
+/// Erases the type of a read/write tuple.
+///
+/// The closures must work with any `dyn Read`/`dyn Write`,
+/// `boxed!` builds them from a parser. Use `lazy` to use 
+/// the returned tuple with any reader/writer.
+///
+/// # Examples
+/// ```
+/// use std::io::{ Cursor, Read, Write };
+/// use bin_io::dynamic::{ boxed, DynParser };
+/// use bin_io::numbers::le_u16;
+/// use bin_io::{ count, read, write };
+///
+/// let parser: DynParser<Vec<u16>> = boxed((
+///     |r: &mut (dyn Read + '_)| read(r, count(le_u16(), 2)),
+///     |w: &mut (dyn Write + '_), v: &Vec<u16>| write(w, v, count(le_u16(), 2))
+/// ));
+///
+/// let vec = vec![ 0x01, 0x00, 0x02, 0x00 ];
+/// let mut cursor = Cursor::new(vec);
+///
+/// let val = (parser.0)(&mut cursor)
+///     .unwrap();
+///
+/// assert_eq!(val, vec![ 1, 2 ]);
+/// ```
+pub fn boxed<'a, Rf, Wf, I>(f: (Rf, Wf)) -> BoxParser<'a, I>
+where Rf: for<'r> ReadFn<dyn Read + 'r, I> + 'a, Wf: for<'r> WriteFn<dyn Write + 'r, I> + 'a {
+
+    let (rf, wf) = f;
+
+    (Box::new(rf), Box::new(wf))
+}
+
+/// Builds a boxed parser only when it's used.
+///
+/// This allows a parser to refer to itself, and adapts a
+/// boxed parser to any reader/writer. `f` can be a function
+/// or a closure, and it's called at most once.
+///
+/// ## Reading/Writing
+/// If more than `DEFAULT_MAX_DEPTH` lazy parsers are nested
+/// an error is returned, instead of overflowing the stack.
+///
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::dynamic::{ lazy, DynParser };
+/// use bin_io::numbers::be_u8;
+/// use bin_io::{ seq, boxed, count, read };
+///
+/// struct Node {
+///     children: Vec<Node>
+/// }
+///
+/// fn node() -> DynParser<Node> {
+///     boxed!(seq!(
+///         Node { children },
+///         len: be_u8(), children.len() as _ =>
+///         children: count(lazy(node), len as usize) =>
+///     ))
+/// }
+///
+/// // A very deep tree
+/// let vec = vec![ 0x01; 1000 ];
+/// let mut cursor = Cursor::new(vec);
+///
+/// let err = read(&mut cursor, lazy(node));
+///
+/// assert!(err.is_err());
+/// ```
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::dynamic::lazy;
+/// use bin_io::numbers::be_u16;
+/// use bin_io::{ boxed, count, read };
+///
+/// let len = 2;
+///
+/// let vec = vec![ 0x00, 0x01, 0x00, 0x02 ];
+/// let mut cursor = Cursor::new(vec);
+///
+/// let val = read(&mut cursor, lazy(move || boxed!(move count(be_u16(), len))))
+///     .unwrap();
+///
+/// assert_eq!(val, vec![ 1, 2 ]);
+/// ```
+pub fn lazy<'a, R, W, F, I>(f: F)
+-> (impl ReadFn<R, I> + 'a, impl WriteFn<W, I> + 'a)
+where R: Read + AsDynRead + ?Sized, W: Write + AsDynWrite + ?Sized, 
+    F: Fn() -> BoxParser<'a, I> + 'a, I: 'a {
+
+    lazy_max(f, DEFAULT_MAX_DEPTH)
+}
+
+/// Builds a boxed parser only when it's used.
+///
+/// Like `lazy`, but with a custom recursion limit.
+pub fn lazy_max<'a, R, W, F, I>(f: F, max: usize)
+-> (impl ReadFn<R, I> + 'a, impl WriteFn<W, I> + 'a)
+where R: Read + AsDynRead + ?Sized, W: Write + AsDynWrite + ?Sized, 
+    F: Fn() -> BoxParser<'a, I> + 'a, I: 'a {
+
+    // Shared by both closures, so the parser is built once
+    let rp = Rc::new((OnceCell::new(), f));
+    let wp = rp.clone();
+
+    (move |r: &mut R| {
+        let _guard = DepthGuard::enter(max)?;
+        let (p, f) = &*rp;
+        (p.get_or_init(f).0)(r.as_dyn_read())
+    },
+    move |w: &mut W, i: &I| {
+        let _guard = DepthGuard::enter(max)?;
+        let (p, f) = &*wp;
+        (p.get_or_init(f).1)(w.as_dyn_write(), i)
+    })
+}
+
+/// Collection of parsers selected by a tag.
+/// 
+/// Used together with `dispatch` to read/write 
+/// messages whose layout depends on a tag.
+pub struct Registry<K, I> {
+    parsers: HashMap<K, DynParser<I>>
+}
+
+impl<K: Eq + Hash, I> Registry<K, I> {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self { parsers: HashMap::new() }
+    }
+
+    /// Registers a parser for a tag, returning the 
+    /// one previously registered, if any.
+    pub fn insert(&mut self, tag: K, parser: DynParser<I>) -> Option<DynParser<I>> {
+        self.parsers.insert(tag, parser)
+    }
+
+    /// Removes the parser registered for a tag.
+    pub fn remove(&mut self, tag: &K) -> Option<DynParser<I>> {
+        self.parsers.remove(tag)
+    }
+
+    /// Returns the parser registered for a tag.
+    pub fn get(&self, tag: &K) -> Option<&DynParser<I>> {
+        self.parsers.get(tag)
+    }
+}
+
+impl<K: Eq + Hash, I> Default for Registry<K, I> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Reads/Writes a tag followed by the value 
+/// described by the parser registered for that tag.
+/// 
+/// ## Reading
+/// The function reads the tag, then reads the value with 
+/// the parser registered for it. If there is none
+/// an error is returned.
+/// 
+/// ## Writing
+/// The function gets the tag of the value with `tag_of`, and 
+/// writes it followed by the value. If no parser is 
+/// registered for the tag an error is returned.
+/// 
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::dynamic::{ dispatch, Registry };
+/// use bin_io::numbers::{ be_u8, be_u16 };
+/// use bin_io::strings::null_utf8_max;
+/// use bin_io::{ seq, boxed, read, write };
+/// 
+/// #[derive(Debug, PartialEq, Eq)]
+/// enum Message {
+///     Ping { seq: u16 },
+///     Text { text: String }
+/// }
+/// 
+/// let mut registry = Registry::new();
+/// 
+/// registry.insert(0x01, boxed!(seq!(
+///     Message::Ping { seq },
+///     seq: be_u16() =>
+/// )));
+/// 
+/// registry.insert(0x02, boxed!(seq!(
+///     Message::Text { text },
+///     text: null_utf8_max(16) =>
+/// )));
+/// 
+/// let tag_of = |m: &Message| match m {
+///     Message::Ping { .. } => 0x01,
+///     Message::Text { .. } => 0x02
+/// };
+/// 
+/// let message = Message::Text { text: "hi".to_owned() };
+/// 
+/// let vec = Vec::new();
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// write(&mut cursor, &message, dispatch(&registry, be_u8(), tag_of))
+///     .unwrap();
+/// 
+/// assert_eq!(cursor.get_ref(), &[ 0x02, b'h', b'i', 0x00 ]);
+/// 
+/// cursor.set_position(0);
+/// 
+/// let other = read(&mut cursor, dispatch(&registry, be_u8(), tag_of))
+///     .unwrap();
+/// 
+/// assert_eq!(other, message);
+/// ```
+/// The registry can be built once and used with any stream.
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::dynamic::{ dispatch, Registry };
+/// use bin_io::numbers::{ be_u8, be_u24, be_u32 };
+/// use bin_io::{ boxed, read };
+/// 
+/// let mut registry = Registry::new();
+/// 
+/// registry.insert(0x01, boxed!(be_u24()));
+/// registry.insert(0x02, boxed!(be_u32()));
+/// 
+/// let tag_of = |v: &u32| match *v > 0xffffff {
+///     true => 0x02,
+///     false => 0x01
+/// };
+/// 
+/// let mut first = Cursor::new(vec![ 0x01, 0x12, 0x34, 0x56 ]);
+/// let val = read(&mut first, dispatch(&registry, be_u8(), tag_of))
+///     .unwrap();
+/// 
+/// assert_eq!(val, 0x123456);
+/// 
+/// let mut second = Cursor::new(vec![ 0x02, 0x12, 0x34, 0x56, 0x78 ]);
+/// let val = read(&mut second, dispatch(&registry, be_u8(), tag_of))
+///     .unwrap();
+/// 
+/// assert_eq!(val, 0x12345678);
+/// ```
+pub fn dispatch<'r, R, W, Rf, Wf, F, K, I>(registry: &'r Registry<K, I>, tag: (Rf, Wf), tag_of: F)
+-> (impl ReadFn<R, I> + Captures<'r>, impl WriteFn<W, I> + Captures<'r>)
+where R: Read + AsDynRead + ?Sized, W: Write + AsDynWrite + ?Sized, 
+    Rf: ReadFn<R, K>, Wf: WriteFn<W, K>, F: Fn(&I) -> K, K: Eq + Hash {
+
+    let (rf, wf) = tag;
+
+    (move |r: &mut R| {
+        size::variable()?;
+        let tag = rf(r)?;
+
+        match registry.get(&tag) {
+            Some((prf, _)) => prf(r.as_dyn_read()),
+            None => Err(Error::from(BinError::UnknownVariant))
+        }
+    },
+    move |w: &mut W, i: &I| {
+        let tag = tag_of(i);
+
+        match registry.get(&tag) {
+            Some((_, pwf)) => {
+                wf(w, &tag)?;
+                pwf(w.as_dyn_write(), i)
+            },
+            None => Err(Error::from(BinError::UnknownVariant))
+        }
+    })
+}
@@ -0,0 +1,307 @@
+//! Contains legacy text encodings related functions.
+//!
+//! This module is only available with the `encoding` feature.
+//!
+//! # Examples
+//! ```
+//! use std::io::Cursor;
+//! use bin_io::encoding::{ null_str, Encoding, Charset };
+//! use bin_io::{ read, write };
+//!
+//! let vec = Vec::new();
+//! let mut cursor = Cursor::new(vec);
+//!
+//! let cp437 = Encoding::strict(Charset::Cp437);
+//!
+//! write(&mut cursor, &"½°".to_owned(), null_str(cp437))
+//!     .unwrap();
+//!
+//! assert_eq!(cursor.get_ref(), &[ 0xab, 0xf8, 0x00 ]);
+//!
+//! // Can't be represented in CP437
+//! let err = write(&mut cursor, &"€".to_owned(), null_str(cp437));
+//! assert!(err.is_err());
+//!
+//! cursor.set_position(0);
+//!
+//! let string = read(&mut cursor, null_str(cp437))
+//!     .unwrap();
+//!
+//! assert_eq!(string, "½°");
+//! ```
+
+use crate::{ WriteFn, ReadFn, BinError };
+use std::io::{ self, Read, Write, Error };
+
+use byteorder::{ ReadBytesExt, WriteBytesExt };
+use encoding_rs::SHIFT_JIS;
+
+/// Supported character sets.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Charset {
+    /// ISO-8859-1.
+    Latin1,
+    /// Windows code page 1252.
+    Windows1252,
+    /// IBM PC code page 437.
+    Cp437,
+    /// Shift-JIS (Windows code page 932 flavour).
+    ShiftJis
+}
+
+/// How to handle characters that can't be converted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    /// Fail with an error both when reading and writing.
+    Strict,
+    /// Replace the character with `U+FFFD` when reading,
+    /// and with `?` when writing.
+    Lossy
+}
+
+/// Text encoding, made of a character set and a `Mode`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Encoding {
+    pub charset: Charset,
+    pub mode: Mode
+}
+
+impl Encoding {
+    pub fn strict(charset: Charset) -> Self {
+        Self { charset, mode: Mode::Strict }
+    }
+
+    pub fn lossy(charset: Charset) -> Self {
+        Self { charset, mode: Mode::Lossy }
+    }
+
+    /// Decodes a string.
+    pub fn decode(&self, bytes: &[u8]) -> io::Result<String> {
+        match self.charset {
+            Charset::ShiftJis => match self.mode {
+                Mode::Strict => SHIFT_JIS
+                    .decode_without_bom_handling_and_without_replacement(bytes)
+                    .map(|s| s.into_owned())
+                    .ok_or_else(|| Error::from(BinError::EncodingFail)),
+                Mode::Lossy => Ok(SHIFT_JIS
+                    .decode_without_bom_handling(bytes).0
+                    .into_owned())
+            },
+            _ => bytes.iter()
+                .map(|b| match (self.decode_byte(*b), self.mode) {
+                    (Some(c), _) => Ok(c),
+                    (None, Mode::Lossy) => Ok(std::char::REPLACEMENT_CHARACTER),
+                    (None, Mode::Strict) => Err(Error::from(BinError::EncodingFail))
+                })
+                .collect()
+        }
+    }
+
+    /// Encodes a string.
+    pub fn encode(&self, s: &str) -> io::Result<Vec<u8>> {
+        let mut vec = Vec::with_capacity(s.len());
+        let mut buf = [0; 4];
+
+        for c in s.chars() {
+            let start = vec.len();
+
+            match self.charset {
+                Charset::ShiftJis => {
+                    let (bytes, _, err) = SHIFT_JIS.encode(c.encode_utf8(&mut buf));
+                    if !err {
+                        vec.extend_from_slice(&bytes[..]);
+                    }
+                },
+                _ => if let Some(b) = self.encode_char(c) {
+                    vec.push(b);
+                }
+            }
+
+            match (vec.len() == start, self.mode) {
+                (false, _) => {},
+                (true, Mode::Lossy) => vec.push(b'?'),
+                (true, Mode::Strict) => return Err(Error::from(BinError::EncodingFail))
+            }
+        }
+
+        Ok(vec)
+    }
+
+    fn decode_byte(&self, b: u8) -> Option<char> {
+        match (self.charset, b) {
+            (_, 0x00..=0x7f) => Some(b as char),
+            (Charset::Latin1, _) => Some(b as char),
+            (Charset::Windows1252, 0x80..=0x9f) => WINDOWS_1252[(b - 0x80) as usize],
+            (Charset::Windows1252, _) => Some(b as char),
+            (Charset::Cp437, _) => Some(CP437[(b - 0x80) as usize]),
+            (Charset::ShiftJis, _) => None
+        }
+    }
+
+    fn encode_char(&self, c: char) -> Option<u8> {
+        match (self.charset, c as u32) {
+            (_, 0x00..=0x7f) => Some(c as u8),
+            (Charset::Latin1, 0x80..=0xff) => Some(c as u8),
+            (Charset::Windows1252, 0xa0..=0xff) => Some(c as u8),
+            (Charset::Windows1252, _) => WINDOWS_1252.iter()
+                .position(|e| *e == Some(c))
+                .map(|i| i as u8 + 0x80),
+            (Charset::Cp437, _) => CP437.iter()
+                .position(|e| *e == c)
+                .map(|i| i as u8 + 0x80),
+            _ => None
+        }
+    }
+}
+
+/// Windows-1252 characters from `0x80` to `0x9f`,
+/// the rest is the same as Latin-1.
+static WINDOWS_1252: [Option<char>; 32] = [
+    Some('€'), None, Some('‚'), Some('ƒ'), Some('„'), Some('…'), Some('†'), Some('‡'),
+    Some('ˆ'), Some('‰'), Some('Š'), Some('‹'), Some('Œ'), None, Some('Ž'), None,
+    None, Some('‘'), Some('’'), Some('“'), Some('”'), Some('•'), Some('–'), Some('—'),
+    Some('˜'), Some('™'), Some('š'), Some('›'), Some('œ'), None, Some('ž'), Some('Ÿ')
+];
+
+/// CP437 characters from `0x80` to `0xff`.
+static CP437: [char; 128] = [
+    'Ç', 'ü', 'é', 'â', 'ä', 'à', 'å', 'ç', 'ê', 'ë', 'è', 'ï', 'î', 'ì', 'Ä', 'Å',
+    'É', 'æ', 'Æ', 'ô', 'ö', 'ò', 'û', 'ù', 'ÿ', 'Ö', 'Ü', '¢', '£', '¥', '₧', 'ƒ',
+    'á', 'í', 'ó', 'ú', 'ñ', 'Ñ', 'ª', 'º', '¿', '⌐', '¬', '½', '¼', '¡', '«', '»',
+    '░', '▒', '▓', '│', '┤', '╡', '╢', '╖', '╕', '╣', '║', '╗', '╝', '╜', '╛', '┐',
+    '└', '┴', '┬', '├', '─', '┼', '╞', '╟', '╚', '╔', '╩', '╦', '╠', '═', '╬', '╧',
+    '╨', '╤', '╥', '╙', '╘', '╒', '╓', '╫', '╪', '┘', '┌', '█', '▄', '▌', '▐', '▀',
+    'α', 'ß', 'Γ', 'π', 'Σ', 'σ', 'µ', 'τ', 'Φ', 'Θ', 'Ω', 'δ', '∞', 'φ', 'ε', '∩',
+    '≡', '±', '≥', '≤', '⌠', '⌡', '÷', '≈', '°', '∙', '·', '√', 'ⁿ', '²', '■', '\u{a0}'
+];
+
+/// Reads/Writes a null terminated string from a stream
+/// using the passed encoding.
+///
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::encoding::{ null_str, Encoding, Charset };
+/// use bin_io::read;
+///
+/// let vec = vec![ 0x83, 0x4c, 0x83, 0x62, 0x83, 0x67, 0x00 ];
+/// let mut cursor = Cursor::new(vec);
+///
+/// let string = read(&mut cursor, null_str(Encoding::strict(Charset::ShiftJis)))
+///     .unwrap();
+///
+/// assert_eq!(string, "キット");
+/// ```
+pub fn null_str<R: Read + ?Sized, W: Write + ?Sized>(encoding: Encoding)
+-> (impl ReadFn<R, String>, impl WriteFn<W, String>) {
+
+    (move |r: &mut R| {
+        let mut s = Vec::new();
+        loop {
+            let c = r.read_u8()?;
+            match c {
+                0 => break,
+                _ => s.push(c)
+            }
+        }
+
+        encoding.decode(&s[..])
+    },
+    move |w: &mut W, s: &String| {
+        w.write_all(&encoding.encode(s)?[..])?;
+        w.write_u8(0)
+    })
+}
+
+/// Reads/Writes a string from a stream given its length
+/// in bytes, using the passed encoding.
+///
+/// # Errors
+/// If the length of the encoded input string is not
+/// `len` an error is returned.
+///
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::encoding::{ len_str, Encoding, Charset };
+/// use bin_io::read;
+///
+/// let vec = vec![ 0x43, 0x61, 0x66, 0xe9, 0x81 ];
+/// let mut cursor = Cursor::new(vec);
+///
+/// // 0x81 is not defined in Windows-1252
+/// let string = read(&mut cursor, len_str(Encoding::lossy(Charset::Windows1252), 5))
+///     .unwrap();
+///
+/// assert_eq!(string, "Café\u{fffd}");
+/// ```
+pub fn len_str<R: Read + ?Sized, W: Write + ?Sized>(encoding: Encoding, len: usize)
+-> (impl ReadFn<R, String>, impl WriteFn<W, String>) {
+
+    (move |r: &mut R| {
+        let mut s = vec![0; len];
+        r.read_exact(&mut s[..])?;
+
+        encoding.decode(&s[..])
+    },
+    move |w: &mut W, s: &String| {
+        let s = encoding.encode(s)?;
+
+        match s.len() == len {
+            true => w.write_all(&s[..]),
+            false => Err(Error::from(BinError::LengthMismatch))
+        }
+    })
+}
+
+/// Reads/Writes a string from a fixed size slot
+/// of `len` bytes, using the passed encoding.
+///
+/// ## Reading
+/// The function reads the whole slot, and returns the
+/// string up to the first null byte.
+///
+/// ## Writing
+/// The function writes the string, and fills the rest
+/// of the slot with null bytes. If the encoded string
+/// is longer than `len` an error is returned.
+///
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::encoding::{ fixed_str, Encoding, Charset };
+/// use bin_io::write;
+///
+/// let vec = Vec::new();
+/// let mut cursor = Cursor::new(vec);
+///
+/// write(&mut cursor, &"Ünï".to_owned(), fixed_str(Encoding::strict(Charset::Latin1), 5))
+///     .unwrap();
+///
+/// assert_eq!(cursor.get_ref(), &[ 0xdc, 0x6e, 0xef, 0x00, 0x00 ]);
+/// ```
+pub fn fixed_str<R: Read + ?Sized, W: Write + ?Sized>(encoding: Encoding, len: usize)
+-> (impl ReadFn<R, String>, impl WriteFn<W, String>) {
+
+    (move |r: &mut R| {
+        let mut s = vec![0; len];
+        r.read_exact(&mut s[..])?;
+
+        let end = s.iter()
+            .position(|b| *b == 0)
+            .unwrap_or(len);
+
+        encoding.decode(&s[..end])
+    },
+    move |w: &mut W, s: &String| {
+        let mut s = encoding.encode(s)?;
+
+        match s.len() <= len {
+            true => {
+                s.resize(len, 0);
+                w.write_all(&s[..])
+            },
+            false => Err(Error::from(BinError::StringTooLong))
+        }
+    })
+}
@@ -1,6 +1,10 @@
 //! Contains error related definitions.
+//! 
+//! Errors returned by `read_with_offset`, `read_buffered` 
+//! and `read_file` carry the offset at which parsing failed,
+//! see `error_offset`.
 
-use std::io::{ Error, ErrorKind };
+use std::io::{ self, Read, Seek, SeekFrom, Error, ErrorKind };
 use quick_error::quick_error;
 
 quick_error! {
@@ -22,12 +26,59 @@ quick_error! {
             description("Failed string conversion")
             from()
         }
+        Utf32Conversion {
+            description("Failed string conversion")
+        }
+        EncodingFail {
+            description("Failed string conversion")
+        }
+        MissingTerminator(max: usize) {
+            description("Missing null terminator")
+            display("No null terminator found in {} bytes", max)
+        }
+        StringTooLong {
+            description("String too long")
+        }
+        LengthMismatch {
+            description("Length doesn't match the expected one")
+        }
         CheckFail {
             description("Check failed")
         }
         CastFail {
             description("Cast failed")
         }
+        OutOfRange {
+            description("Value out of range")
+        }
+        UnknownVariant {
+            description("Unknown enum variant")
+        }
+        ConditionMismatch {
+            description("Conditional fields don't match their condition")
+        }
+        DuplicateKey {
+            description("Duplicate key")
+        }
+        UnknownFlags {
+            description("Unknown flag bits")
+        }
+        RecursionLimit(max: usize) {
+            description("Recursion limit reached")
+            display("Recursion limit of {} reached", max)
+        }
+        ChecksumFail {
+            description("Checksum mismatch")
+        }
+        Context { offset: u64, inner: Error } {
+            cause(inner)
+            description("Parsing failed")
+            display("{} (at offset {})", inner, offset)
+        }
+        Serde(msg: String) {
+            description("Serde error")
+            display("Serde error: {}", msg)
+        }
     }
 }
 
@@ -38,4 +89,102 @@ impl From<BinError> for Error {
             err
         )
     }
-}
\ No newline at end of file
+}
+
+/// Returns the offset at which an error returned
+/// by `read_with_offset` occurred.
+/// 
+/// The offset is the position in the stream right 
+/// after the last byte consumed before the error.
+/// 
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::numbers::{ be_u8, be_u32 };
+/// use bin_io::error::error_offset;
+/// use bin_io::{ seq, read_with_offset };
+/// 
+/// struct Thing {
+///     a: u8,
+///     b: u32
+/// }
+/// 
+/// // Stream ends while reading b
+/// let vec = vec![ 0x10, 0x00, 0x00 ];
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// let err = read_with_offset(&mut cursor, seq!(
+///     Thing { a, b },
+///     a: be_u8() =>
+///     b: be_u32() =>
+/// )).err().unwrap();
+/// 
+/// assert_eq!(error_offset(&err), Some(3));
+/// ```
+pub fn error_offset(err: &Error) -> Option<u64> {
+    match err.get_ref()?.downcast_ref::<BinError>()? {
+        BinError::Context { offset, .. } => Some(*offset),
+        _ => None
+    }
+}
+
+/// Reader that keeps track of its position in the stream.
+/// 
+/// This is used by `read_with_offset` to report 
+/// the offset of errors.
+#[derive(Debug)]
+pub struct Position<R> {
+    inner: R,
+    pos: u64
+}
+
+impl<R> Position<R> {
+    /// Creates a `Position` starting at `pos`.
+    pub fn new(inner: R, pos: u64) -> Self {
+        Self { inner, pos }
+    }
+
+    /// Returns the current position.
+    pub fn position(&self) -> u64 {
+        self.pos
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Attaches the current position to an error.
+    /// 
+    /// If the error already has a position (because of 
+    /// a nested `read_with_offset`) it's left as is, so 
+    /// positions aren't stacked.
+    pub fn context(&self, err: Error) -> Error {
+        let nested = matches!(
+            err.get_ref().and_then(|e| e.downcast_ref::<BinError>()), 
+            Some(BinError::Context { .. })
+        );
+
+        match nested {
+            true => err,
+            false => Error::new(err.kind(), BinError::Context { offset: self.pos, inner: err })
+        }
+    }
+}
+
+impl<R: Read> Read for Position<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.pos += n as u64;
+        Ok(n)
+    }
+}
+
+impl<R: Seek> Seek for Position<R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let old = self.inner.stream_position()?;
+        let new = self.inner.seek(pos)?;
+
+        self.pos = (self.pos as i64 + (new as i64 - old as i64)) as u64;
+        Ok(new)
+    }
+}
@@ -52,6 +52,79 @@
 //! work fine (with minor code changes, `seq!` in particular), 
 //! so check out the documentation!
 //! 
+//! # Passing context to child parsers
+//! Parsers don't need a separate context parameter: the 
+//! arguments of a parser function *are* its context. Inside 
+//! `seq!` every entry is built right before it's read or 
+//! written, so a reusable child parser can receive the values 
+//! of its parent (a version, a flag, an offset), and this works
+//! the same way on both paths. Since the returned parser
+//! must own its arguments, use `seq!(move ...)` to move them 
+//! into the closures.
+//! ```
+//! use std::io::Cursor;
+//! use bin_io::{ boilerplate, seq, count, read, write };
+//! use bin_io::numbers::{ be_u8, be_u16 };
+//! 
+//! #[derive(Debug, PartialEq, Eq)]
+//! struct Entry {
+//!     id: u16,
+//!     flags: Option<u8>
+//! }
+//! 
+//! #[derive(Debug, PartialEq, Eq)]
+//! struct Header {
+//!     version: u8,
+//!     entries: Vec<Entry>
+//! }
+//! 
+//! boilerplate!(
+//!     // Flags were added in version 2
+//!     fn entry(version: u8) -> Entry {
+//!         seq!(
+//!             move Entry { id, flags },
+//!             id: be_u16() =>
+//!             if version >= 2 {
+//!                 flags: be_u8() =>
+//!             }
+//!         )
+//!     }
+//! );
+//! 
+//! boilerplate!(
+//!     fn header() -> Header {
+//!         seq!(
+//!             Header { version, entries },
+//!             version: be_u8() =>
+//!             len: be_u8(), entries.len() as _ =>
+//!             entries: count(entry(version), len as usize) =>
+//!         )
+//!     }
+//! );
+//! 
+//! let value = Header { 
+//!     version: 1, 
+//!     entries: vec![ 
+//!         Entry { id: 0x10, flags: None }, 
+//!         Entry { id: 0x20, flags: None } 
+//!     ] 
+//! };
+//! 
+//! let mut cursor = Cursor::new(Vec::new());
+//! 
+//! write(&mut cursor, &value, header())
+//!     .unwrap();
+//! 
+//! assert_eq!(cursor.get_ref(), &[ 0x01, 0x02, 0x00, 0x10, 0x00, 0x20 ]);
+//! 
+//! cursor.set_position(0);
+//! 
+//! let other = read(&mut cursor, header())
+//!     .unwrap();
+//! 
+//! assert_eq!(other, value);
+//! ```
+//! 
 //! # `nom` or `bin_io`?
 //! `bin_io` is at a very early stage of development, so
 //! you might want to prefer `nom` over `bin_io` for its
@@ -77,22 +150,35 @@ pub mod error;
 pub mod macros;
 pub mod numbers;
 pub mod strings;
+pub mod size;
+pub mod checksum;
+pub mod dynamic;
+pub mod collections;
+#[cfg(feature = "serde")]
+pub mod serde_bridge;
+#[cfg(feature = "encoding")]
+pub mod encoding;
 
 pub use utils::*;
 pub use error::BinError;
 
-use std::io::{ self, Read, Write };
+use size::{ SizeCounter, SizeQuery };
+use error::Position;
+
+use std::io::{ self, Read, Write, Seek, SeekFrom, BufReader, BufWriter };
+use std::fs::File;
+use std::path::Path;
 
 type ReadDummy = Box<dyn Read>;
 type WriteDummy = Box<dyn Write>;
 
 /// Trait representing a read closure.
-pub trait ReadFn<R: Read, I>: Fn(&mut R) -> io::Result<I> { }
-impl<R: Read, I, F: Fn(&mut R) -> io::Result<I>> ReadFn<R, I> for F { }
+pub trait ReadFn<R: Read + ?Sized, I>: Fn(&mut R) -> io::Result<I> { }
+impl<R: Read + ?Sized, I, F: Fn(&mut R) -> io::Result<I>> ReadFn<R, I> for F { }
 
 /// Trait representing a write closure.
-pub trait WriteFn<W: Write, I>: Fn(&mut W, &I) -> io::Result<()> { }
-impl<W: Write, I, F: Fn(&mut W, &I) -> io::Result<()>> WriteFn<W, I> for F { }
+pub trait WriteFn<W: Write + ?Sized, I>: Fn(&mut W, &I) -> io::Result<()> { }
+impl<W: Write + ?Sized, I, F: Fn(&mut W, &I) -> io::Result<()>> WriteFn<W, I> for F { }
 
 /// Reads from a read/write tuple.
 /// 
@@ -112,10 +198,48 @@ impl<W: Write, I, F: Fn(&mut W, &I) -> io::Result<()>> WriteFn<W, I> for F { }
 /// ```
 pub fn read<R, Rf, Wf, I>(r: &mut R, f: (Rf, Wf)) 
 -> io::Result<I>
-where R: Read, Rf: ReadFn<R, I>, Wf: WriteFn<WriteDummy, I> {
+where R: Read + ?Sized, Rf: ReadFn<R, I>, Wf: WriteFn<WriteDummy, I> {
     f.0(r)
 }
 
+/// Reads from a read/write tuple, attaching to errors
+/// the offset at which they occurred.
+/// 
+/// # Remarks
+/// The parser reads through a `Position` starting from
+/// the current position of the stream, so offsets are
+/// absolute, see `error::error_offset`.
+/// 
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::numbers::{ be_u32 };
+/// use bin_io::error::error_offset;
+/// use bin_io::read_with_offset;
+/// 
+/// let vec = vec![ 0x00, 0x00, 0x00, 0x01, 0x00, 0x00 ];
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// let val = read_with_offset(&mut cursor, be_u32())
+///     .unwrap();
+/// 
+/// assert_eq!(val, 1);
+/// 
+/// let err = read_with_offset(&mut cursor, be_u32())
+///     .unwrap_err();
+/// 
+/// assert_eq!(error_offset(&err), Some(6));
+/// ```
+pub fn read_with_offset<'a, R, Rf, Wf, I>(r: &'a mut R, f: (Rf, Wf)) 
+-> io::Result<I>
+where R: Read + Seek + ?Sized, Rf: ReadFn<Position<&'a mut R>, I>, Wf: WriteFn<WriteDummy, I> {
+    let start = r.stream_position()?;
+    let mut r = Position::new(r, start);
+
+    f.0(&mut r)
+        .map_err(|err| r.context(err))
+}
+
 /// Writes to a read/write tuple.
 /// 
 /// # Examples
@@ -135,6 +259,236 @@ where R: Read, Rf: ReadFn<R, I>, Wf: WriteFn<WriteDummy, I> {
 /// ```
 pub fn write<W, Rf, Wf, I>(w: &mut W, i: &I, f: (Rf, Wf))
 -> io::Result<()> 
-where W: Write, Rf: ReadFn<ReadDummy, I>, Wf: WriteFn<W, I> {
+where W: Write + ?Sized, Rf: ReadFn<ReadDummy, I>, Wf: WriteFn<W, I> {
     f.1(w, i)
+}
+
+/// Reads from a read/write tuple using an internal buffer.
+/// 
+/// This is useful with unbuffered streams (such as `File`),
+/// where small reads (like the ones of `null_utf8`) would 
+/// otherwise end up in a syscall each.
+/// 
+/// # Remarks
+/// Once done, either successfully or not, the stream is 
+/// moved back to the position right after the last byte 
+/// consumed by the parser, so it can still be used 
+/// afterwards.
+/// 
+/// Like `read_with_offset`, errors carry the offset
+/// at which they occurred.
+/// 
+/// # Examples
+/// ```
+/// use std::io::{ Cursor, Seek };
+/// use bin_io::strings::null_utf8;
+/// use bin_io::read_buffered;
+/// 
+/// let vec = vec![ 0x46, 0x6f, 0x6f, 0x00, 0x42 ];
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// let string = read_buffered(&mut cursor, null_utf8())
+///     .unwrap();
+/// 
+/// assert_eq!(string, "Foo");
+/// assert_eq!(cursor.stream_position().unwrap(), 4);
+/// ```
+pub fn read_buffered<'a, R, Rf, Wf, I>(r: &'a mut R, f: (Rf, Wf))
+-> io::Result<I>
+where R: Read + Seek + ?Sized, Rf: ReadFn<Position<BufReader<&'a mut R>>, I>, Wf: WriteFn<WriteDummy, I> {
+    let start = r.stream_position()?;
+    let mut r = Position::new(BufReader::new(r), start);
+    let res = f.0(&mut r)
+        .map_err(|err| r.context(err));
+
+    let r = r.into_inner();
+    let unread = r.buffer().len() as i64;
+    let seek = r.into_inner().seek(SeekFrom::Current(-unread));
+
+    let i = res?;
+    seek?;
+
+    Ok(i)
+}
+
+/// Writes to a read/write tuple using an internal buffer.
+/// 
+/// This is useful with unbuffered streams (such as `File`),
+/// where small writes would otherwise end up in a syscall each.
+/// 
+/// # Remarks
+/// Once done, either successfully or not, everything written
+/// by the parser is flushed to the stream.
+/// 
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::numbers::{ be_u16 };
+/// use bin_io::{ write_buffered, count };
+/// 
+/// let vec = Vec::new();
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// write_buffered(&mut cursor, &vec![ 0x10, 0x20 ], count(be_u16(), 2))
+///     .unwrap();
+/// 
+/// assert_eq!(cursor.get_ref(), &[ 0x00, 0x10, 0x00, 0x20 ]);
+/// ```
+pub fn write_buffered<'a, W, Rf, Wf, I>(w: &'a mut W, i: &I, f: (Rf, Wf))
+-> io::Result<()>
+where W: Write + ?Sized, Rf: ReadFn<ReadDummy, I>, Wf: WriteFn<BufWriter<&'a mut W>, I> {
+    let mut w = BufWriter::new(w);
+    let res = f.1(&mut w, i);
+    let flush = w.flush();
+
+    res?;
+    flush
+}
+
+/// Reads a file from a read/write tuple.
+/// 
+/// The file is read using an internal buffer, 
+/// see `read_buffered`, and errors carry the offset
+/// at which they occurred.
+/// 
+/// # Examples
+/// ```
+/// use bin_io::numbers::{ le_u32 };
+/// use bin_io::{ read_file, write_file };
+/// 
+/// let path = std::env::temp_dir().join("bin_io_read_file.bin");
+/// 
+/// write_file(&path, &0xdeadbeef, le_u32())
+///     .unwrap();
+/// 
+/// let val = read_file(&path, le_u32())
+///     .unwrap();
+/// 
+/// assert_eq!(val, 0xdeadbeef);
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub fn read_file<P, Rf, Wf, I>(path: P, f: (Rf, Wf))
+-> io::Result<I>
+where P: AsRef<Path>, Rf: ReadFn<Position<BufReader<File>>, I>, Wf: WriteFn<WriteDummy, I> {
+    let mut r = Position::new(BufReader::new(File::open(path)?), 0);
+
+    f.0(&mut r)
+        .map_err(|err| r.context(err))
+}
+
+/// Writes a file from a read/write tuple, 
+/// replacing it if it already exists.
+/// 
+/// The file is written using an internal buffer, 
+/// see `write_buffered`.
+pub fn write_file<P, Rf, Wf, I>(path: P, i: &I, f: (Rf, Wf))
+-> io::Result<()>
+where P: AsRef<Path>, Rf: ReadFn<ReadDummy, I>, Wf: WriteFn<BufWriter<File>, I> {
+    let mut w = BufWriter::new(File::create(path)?);
+    let res = f.1(&mut w, i);
+    let flush = w.flush();
+
+    res?;
+    flush
+}
+
+/// Computes the number of bytes written by a read/write tuple,
+/// without writing anything.
+/// 
+/// # Examples
+/// ```
+/// use bin_io::numbers::{ be_u8, be_u16 };
+/// use bin_io::{ seq, count, size_of };
+/// 
+/// struct Table {
+///     offsets: Vec<u16>
+/// }
+/// 
+/// let table = Table { offsets: vec![ 0x10, 0x20, 0x30 ] };
+/// 
+/// let size = size_of(&table, seq!(
+///     Table { offsets },
+///     len: be_u8(), offsets.len() as u8 =>
+///     offsets: count(be_u16(), len as usize) =>
+/// )).unwrap();
+/// 
+/// assert_eq!(size, 7);
+/// ```
+pub fn size_of<Rf, Wf, I>(i: &I, f: (Rf, Wf))
+-> io::Result<usize>
+where Rf: ReadFn<ReadDummy, I>, Wf: WriteFn<SizeCounter, I> {
+    let mut counter = SizeCounter::new();
+    f.1(&mut counter, i)?;
+
+    Ok(counter.count())
+}
+
+/// Computes the number of bytes read by a read/write tuple,
+/// if it doesn't depend on the data.
+/// 
+/// The tuple is built like for `read`, so this works on 
+/// any composition of parsers (`count`, `array`, `seq!`...).
+/// 
+/// # Remarks
+/// Nothing is read: the parsers report their size with 
+/// `size::fixed` and make up the values, while the ones 
+/// reading something that depends on the data (strings, 
+/// `optional`, `if`/`match` groups in `seq!`...) make the 
+/// function return `None`. Since a value can still be used 
+/// as the argument of a later parser (think of a length
+/// passed to `count`), the tuple is run twice with different
+/// values, and the two sizes must match.
+/// 
+/// # Examples
+/// ```
+/// use bin_io::numbers::{ be_u8, be_u16, be_u24, be_u32 };
+/// use bin_io::strings::null_utf8;
+/// use bin_io::{ seq, bind, count, optional, fixed_size };
+/// 
+/// struct Table {
+///     offsets: Vec<u16>
+/// }
+/// 
+/// struct Point {
+///     x: u32,
+///     y: u32
+/// }
+/// 
+/// struct Entry {
+///     flag: u8,
+///     extra: Option<u8>
+/// }
+/// 
+/// assert_eq!(fixed_size(be_u24()), Some(3));
+/// assert_eq!(fixed_size(count(be_u16(), 3)), Some(6));
+/// assert_eq!(fixed_size(null_utf8()), None);
+/// 
+/// assert_eq!(fixed_size(seq!(
+///     Point { x, y },
+///     bind(be_u32(), 0xcafebabe) =>
+///     x: be_u32() =>
+///     y: be_u32() =>
+/// )), Some(12));
+/// 
+/// assert_eq!(fixed_size(seq!(
+///     Table { offsets },
+///     len: be_u8(), offsets.len() as u8 =>
+///     offsets: count(be_u16(), len as usize) =>
+/// )), None);
+/// 
+/// assert_eq!(fixed_size(seq!(
+///     Entry { flag, extra },
+///     flag: be_u8() =>
+///     extra: optional(be_u8(), flag == 2) =>
+/// )), None);
+/// ```
+pub fn fixed_size<Rf, Wf, I>(f: (Rf, Wf))
+-> Option<usize>
+where Rf: ReadFn<SizeQuery, I>, Wf: WriteFn<WriteDummy, I> {
+    let first = size::query(0, &f.0)?;
+
+    match size::query(1, &f.0)? == first {
+        true => Some(first),
+        false => None
+    }
 }
\ No newline at end of file
@@ -1,54 +1,78 @@
+use crate::{ ReadFn, WriteFn };
+use std::io::{ self, Read, Write };
+
 /// Macro used to generate a write/read tuple 
 /// from sequence of operations.
 /// 
 /// # Remarks
-/// Due to internal limitations assigning a
-/// different name to a variable in the capture
-/// is not valid:
-/// ```compile_fail
-/// // Won't compile!!
-/// seq!(
-///     Bar { a: a1, b: b1 },
-///     a1: be_u8 => 
-///     b1: be_u8 =>
-/// )
-/// ```
-/// While reading variables used are owned copies of the
-/// values, while writing the values are references to 
-/// those values, so it's necessary that you always use
-/// either as_ref() or to_owned() to collapse the two
-/// states into a reference or an owned copy.
+/// While reading variables are owned values, while writing 
+/// they are references to the fields. To make expressions
+/// work on both paths, once a field is written, `Copy` values
+/// are shadowed with a copy of themselves, so that 
+/// `len as usize` or `flags & 1 != 0` can be used directly.
+/// Other values (like a `Vec`) are left as references, so 
+/// method calls (`a.len()`) still work in both cases.
 /// ```
 /// use std::io::Cursor;
-/// use bin_io::{ seq, count, read };
+/// use bin_io::{ seq, count, optional, read };
 /// use bin_io::numbers::{ be_u8, be_i16 };
 /// 
 /// #[derive(Debug, PartialEq, Eq)]
 /// struct Foo {
-///     a: Vec<i16>
+///     flags: u8,
+///     a: Vec<i16>,
+///     b: Option<u8>
 /// }
 /// 
-/// /* Won't compile
 /// let tuple = seq!(
-///     Foo { a },
+///     Foo { flags, a, b },
+///     flags: be_u8() =>
 ///     len: be_u8(), a.len() as _ =>
 ///     a: count(be_i16(), len as usize) =>
+///     b: optional(be_u8(), flags & 1 != 0) =>
 /// );
-/// */
 /// 
-/// let tuple = seq!(
-///     Foo { a },
-///     len: be_u8(), a.len() as _ =>
-///     a: count(be_i16(), len.to_owned() as usize) =>
-/// );
-/// 
-/// let mut vec = vec![ 0x02, 0x00, 0x01, 0x00, 0x02 ];
+/// let mut vec = vec![ 0x00, 0x02, 0x00, 0x01, 0x00, 0x02 ];
 /// let mut cursor = Cursor::new(&mut vec);
 /// 
 /// let foo = read(&mut cursor, tuple)
 ///     .unwrap();
 /// 
-/// assert_eq!(foo, Foo { a: vec![ 1, 2 ] });
+/// assert_eq!(foo, Foo { flags: 0, a: vec![ 1, 2 ], b: None });
+/// ```
+/// Nested `seq!` can use the variables of the outer one.
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::{ seq, boilerplate, count, write };
+/// use bin_io::numbers::be_u8;
+/// 
+/// struct Inner {
+///     data: Vec<u8>
+/// }
+/// 
+/// struct Outer {
+///     inner: Inner
+/// }
+/// 
+/// boilerplate!(
+///     fn outer() -> Outer {
+///         seq!(
+///             Outer { inner },
+///             len: be_u8(), inner.data.len() as _ =>
+///             inner: seq!(
+///                 Inner { data },
+///                 data: count(be_u8(), len as usize) =>
+///             ) =>
+///         )
+///     }
+/// );
+/// 
+/// let mut cursor = Cursor::new(Vec::new());
+/// 
+/// write(&mut cursor, &Outer { inner: Inner { data: vec![ 0x10, 0x20 ] } }, outer())
+///     .unwrap();
+/// 
+/// assert_eq!(cursor.get_ref(), &[ 0x02, 0x10, 0x20 ]);
 /// ```
 /// # Examples
 /// ```
@@ -76,7 +100,7 @@
 ///     a: be_u8() =>
 ///     b: le_u16() =>
 ///     skip(be_u16(), 1557) =>
-///     c: count(be_i32(), b.to_owned() as usize) =>
+///     c: count(be_i32(), b as usize) =>
 ///     d: null_utf16() =>
 /// );
 /// 
@@ -88,7 +112,9 @@
 /// let test = read(r, tuple)
 ///     .unwrap();
 /// ```
-/// `seq!` is compatible with multiple data structures 
+/// `seq!` is compatible with multiple data structures, 
+/// enum variants included (writing a different variant
+/// returns an error)
 /// ```
 /// use std::io::Cursor;
 /// use bin_io::{ seq, read, bind };
@@ -146,7 +172,7 @@
 ///     // Give the field a default value or some expression to initialize it
 ///     // Remember: this value is only used during writing and not reading
 ///     length: be_u8(), a.len() as u8 =>
-///     a: count(be_i16(), length.to_owned() as _) =>
+///     a: count(be_i16(), length as _) =>
 /// );
 /// 
 /// let vec = vec![ 0x2, 0x0, 0x50, 0x0, 0x60 ];
@@ -157,50 +183,387 @@
 /// 
 /// assert_eq!(foo, Foo { a: vec![ 0x50, 0x60 ] })
 /// ```
+/// Fields can be bound to different names, and nested structures
+/// can be destructured in place. Fields can also be derived from
+/// the previous variables with `name = expr =>`: they are computed
+/// on both paths (so the following entries can use them), but 
+/// never read from or written to the stream.
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::{ seq, boilerplate, read, write };
+/// use bin_io::numbers::be_u8;
+/// 
+/// #[derive(Debug, PartialEq, Eq)]
+/// struct Point {
+///     x: u8,
+///     y: u8
+/// }
+/// 
+/// #[derive(Debug, PartialEq, Eq)]
+/// struct Line {
+///     from: Point,
+///     to: Point,
+///     width: u8
+/// }
+/// 
+/// boilerplate!(
+///     fn line() -> Line {
+///         seq!(
+///             Line { from: Point { x: x1, y: y1 }, to: Point { x: x2, y: y2 }, width },
+///             x1: be_u8() =>
+///             y1: be_u8() =>
+///             x2: be_u8() =>
+///             y2: be_u8() =>
+///             width = x2 - x1 =>
+///         )
+///     }
+/// );
+/// 
+/// let vec = vec![ 0x01, 0x02, 0x04, 0x05 ];
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// let value = read(&mut cursor, line())
+///     .unwrap();
+/// 
+/// assert_eq!(value, Line { 
+///     from: Point { x: 1, y: 2 }, 
+///     to: Point { x: 4, y: 5 },
+///     width: 3
+/// });
+/// 
+/// let mut cursor = Cursor::new(Vec::new());
+/// 
+/// write(&mut cursor, &value, line())
+///     .unwrap();
+/// 
+/// assert_eq!(cursor.get_ref(), &[ 0x01, 0x02, 0x04, 0x05 ]);
+/// ```
+/// Derived values don't have to be fields of the structure.
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::{ seq, boilerplate, count, write };
+/// use bin_io::numbers::be_u8;
+/// 
+/// struct Block {
+///     a: u8,
+///     b: u8,
+///     data: Vec<u8>
+/// }
+/// 
+/// boilerplate!(
+///     fn block() -> Block {
+///         seq!(
+///             Block { a, b, data },
+///             a: be_u8() =>
+///             b: be_u8() =>
+///             total = a + b =>
+///             data: count(be_u8(), total as usize) =>
+///         )
+///     }
+/// );
+/// 
+/// let value = Block { a: 1, b: 1, data: vec![ 0x10, 0x20 ] };
+/// let mut cursor = Cursor::new(Vec::new());
+/// 
+/// write(&mut cursor, &value, block())
+///     .unwrap();
+/// 
+/// assert_eq!(cursor.get_ref(), &[ 0x01, 0x01, 0x10, 0x20 ]);
+/// ```
+/// Groups of fields can depend on the previous variables. 
+/// The fields of an `if` group become `Option`s, while a `match`
+/// group reads/writes a variant of an enum for each arm. While writing
+/// if the fields don't match the condition an error is returned.
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::{ seq, boilerplate, read, write };
+/// use bin_io::numbers::{ be_u8, be_u16, be_u32 };
+/// use bin_io::strings::null_utf8;
+/// 
+/// #[derive(Debug, PartialEq, Eq)]
+/// enum Body { 
+///     Text(String), 
+///     Point { x: u8, y: u8 }, 
+///     Empty 
+/// }
+/// 
+/// #[derive(Debug, PartialEq, Eq)]
+/// struct Record { 
+///     version: u8, 
+///     extra: Option<u32>, 
+///     more: Option<u16>, 
+///     body: Body 
+/// }
+/// 
+/// boilerplate!(
+///     fn record() -> Record {
+///         seq!(
+///             Record { version, extra, more, body },
+///             version: be_u8() =>
+///             if version >= 2 {
+///                 extra: be_u32() =>
+///                 more: be_u16() =>
+///             }
+///             kind: be_u8(), match body { 
+///                 Body::Text(_) => 1, 
+///                 Body::Point { .. } => 2, 
+///                 Body::Empty => 3 
+///             } =>
+///             body: match kind {
+///                 1 => Body::Text(text) { text: null_utf8() => },
+///                 2 => Body::Point { x, y } { x: be_u8() => y: be_u8() => },
+///                 3 => Body::Empty { },
+///             } =>
+///         )
+///     }
+/// );
+/// 
+/// let vec = vec![ 0x01, 0x02, 0x10, 0x20 ];
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// let value = read(&mut cursor, record())
+///     .unwrap();
+/// 
+/// assert_eq!(value, Record { 
+///     version: 1, 
+///     extra: None, 
+///     more: None, 
+///     body: Body::Point { x: 0x10, y: 0x20 } 
+/// });
+/// 
+/// // Version 1 doesn't have extra fields
+/// let value = Record { version: 1, extra: Some(0), more: None, body: Body::Empty };
+/// let err = write(&mut cursor, &value, record());
+/// 
+/// assert!(err.is_err());
+/// ```
+/// Variables with a write default are local to their group.
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::{ seq, boilerplate, count, read, write };
+/// use bin_io::numbers::be_u8;
+/// 
+/// #[derive(Debug, PartialEq, Eq)]
+/// struct Packet {
+///     flags: u8,
+///     items: Option<Vec<u8>>
+/// }
+/// 
+/// boilerplate!(
+///     fn packet() -> Packet {
+///         seq!(
+///             Packet { flags, items },
+///             flags: be_u8() =>
+///             if flags & 1 != 0 {
+///                 len: be_u8(), items.len() as _ =>
+///                 items: count(be_u8(), len as usize) =>
+///             }
+///         )
+///     }
+/// );
+/// 
+/// let value = Packet { flags: 1, items: Some(vec![ 0x10, 0x20 ]) };
+/// let mut cursor = Cursor::new(Vec::new());
+/// 
+/// write(&mut cursor, &value, packet())
+///     .unwrap();
+/// 
+/// assert_eq!(cursor.get_ref(), &[ 0x01, 0x02, 0x10, 0x20 ]);
+/// 
+/// cursor.set_position(0);
+/// 
+/// let other = read(&mut cursor, packet())
+///     .unwrap();
+/// 
+/// assert_eq!(other, value);
+/// ```
+/// Like closures, `seq!` borrows the variables it uses. To 
+/// capture the arguments of a parser function, prefix the 
+/// structure with `move`.
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::{ seq, boilerplate, count, read };
+/// use bin_io::numbers::be_u8;
+/// 
+/// struct Block {
+///     data: Vec<u8>
+/// }
+/// 
+/// boilerplate!(
+///     fn block(size: usize) -> Block {
+///         seq!(
+///             move Block { data },
+///             data: count(be_u8(), size) =>
+///         )
+///     }
+/// );
+/// 
+/// let vec = vec![ 0x01, 0x02, 0x03 ];
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// let value = read(&mut cursor, block(2))
+///     .unwrap();
+/// 
+/// assert_eq!(value.data, vec![ 0x01, 0x02 ]);
+/// ```
+/// The captured variables are cloned, so they don't have to be `Copy`.
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::{ seq, boilerplate, count, read, write };
+/// use bin_io::numbers::be_u8;
+/// 
+/// #[derive(Debug, PartialEq, Eq)]
+/// struct Table {
+///     a: Vec<u8>,
+///     b: Vec<u8>
+/// }
+/// 
+/// boilerplate!(
+///     fn table(sizes: Vec<usize>) -> Table {
+///         seq!(
+///             move Table { a, b },
+///             a: count(be_u8(), sizes[0]) =>
+///             b: count(be_u8(), sizes[1]) =>
+///         )
+///     }
+/// );
+/// 
+/// let value = Table { a: vec![ 0x01 ], b: vec![ 0x02, 0x03 ] };
+/// let mut cursor = Cursor::new(Vec::new());
+/// 
+/// write(&mut cursor, &value, table(vec![ 1, 2 ]))
+///     .unwrap();
+/// 
+/// cursor.set_position(0);
+/// 
+/// let other = read(&mut cursor, table(vec![ 1, 2 ]))
+///     .unwrap();
+/// 
+/// assert_eq!(other, value);
+/// ```
+/// This is also how a parser gets context from its parent 
+/// (a version, a flag, a length): it takes it as an argument
+/// of its parser function and captures it with `seq!(move ...)`.
+/// The captured variables are cloned for each closure and the 
+/// inner parsers are built again on every call, so the parent 
+/// can pass a value it has just read (or is about to write).
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::{ seq, boilerplate, count, read, write };
+/// use bin_io::numbers::be_u8;
+/// 
+/// #[derive(Debug, PartialEq, Eq)]
+/// struct Entry {
+///     name: Vec<u8>
+/// }
+/// 
+/// #[derive(Debug, PartialEq, Eq)]
+/// struct Archive {
+///     version: u8,
+///     entry: Entry
+/// }
+/// 
+/// boilerplate!(
+///     // Names got longer in version 2
+///     fn entry_parser(version: u8) -> Entry {
+///         seq!(
+///             move Entry { name },
+///             name: count(be_u8(), match version { 1 => 2, _ => 4 }) =>
+///         )
+///     }
+/// );
+/// 
+/// boilerplate!(
+///     fn archive() -> Archive {
+///         seq!(
+///             Archive { version, entry },
+///             version: be_u8() =>
+///             entry: entry_parser(version) =>
+///         )
+///     }
+/// );
+/// 
+/// let value = Archive { version: 2, entry: Entry { name: vec![ 0x41; 4 ] } };
+/// let mut cursor = Cursor::new(Vec::new());
+/// 
+/// write(&mut cursor, &value, archive())
+///     .unwrap();
+/// 
+/// assert_eq!(cursor.get_ref(), &[ 0x02, 0x41, 0x41, 0x41, 0x41 ]);
+/// 
+/// let vec = vec![ 0x01, 0x41, 0x42, 0x43 ];
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// let other = read(&mut cursor, archive())
+///     .unwrap();
+/// 
+/// assert_eq!(other, Archive { version: 1, entry: Entry { name: vec![ 0x41, 0x42 ] } });
+/// ```
 #[macro_export]
 macro_rules! seq {
-    ($($ty:ident)::+ { $($field:ident),* }, $($rest:tt)*) => {
+    // Both closures need the captured variables, so they're 
+    // built inside a single `move` closure that is cloned.
+    (move $($rest:tt)*) => {
+        $crate::macros::split(move |op| {
+            let (rf, wf) = $crate::seq!(__head $($rest)*);
+            match op {
+                $crate::macros::Op::Read(r) => rf(r).map(Some),
+                $crate::macros::Op::Write(w, v) => wf(w, v).map(|_| None)
+            }
+        })
+    };
+
+    (__head $($ty:ident)::+ { $($field:tt)* }, $($rest:tt)*) => {
         (|r: &mut _| {
             $crate::seq!(__impl r $($ty)::* {
-                $($field),*
+                $($field)*
             }, r, $($rest)*)
         },
         |w: &mut _, v: &_| {
+            #[allow(irrefutable_let_patterns)]
             let $($ty)::* {
-                $($field),*
-            } = v;
+                $($field)*
+            } = v else {
+                return Err(::std::io::Error::from($crate::BinError::ConditionMismatch));
+            };
             $crate::seq!(__impl w w, $($rest)*);
             Ok(())
         })
     };
 
-    ($($ty:ident)::+ ( $($field:ident),* ), $($rest:tt)*) => {
+    (__head $($ty:ident)::+ ( $($field:tt)* ), $($rest:tt)*) => {
         (|r: &mut _| {
             $crate::seq!(__impl r $($ty)::* (
-                $($field),*
+                $($field)*
             ), r, $($rest)*)
         },
         |w: &mut _, v: &_| {
+            #[allow(irrefutable_let_patterns)]
             let $($ty)::* (
-                $($field),*
-            ) = v;
+                $($field)*
+            ) = v else {
+                return Err(::std::io::Error::from($crate::BinError::ConditionMismatch));
+            };
             $crate::seq!(__impl w w, $($rest)*);
             Ok(())
         })
     };
 
-    ($($ty:ident)::+, $($rest:tt)*) => {
+    (__head $($ty:ident)::+, $($rest:tt)*) => {
         (|r: &mut _| {
             $crate::seq!(__impl r $($ty)::*, r, $($rest)*)
         },
         |w: &mut _, v: &_| {
-            let $($ty)::* = v;
+            #[allow(irrefutable_let_patterns)]
+            let $($ty)::* = v else {
+                return Err(::std::io::Error::from($crate::BinError::ConditionMismatch));
+            };
             $crate::seq!(__impl w w, $($rest)*);
             Ok(())
         })
     };
 
-    ((), $($rest:tt)*) => {
+    (__head (), $($rest:tt)*) => {
         (|r: &mut _| {
             $crate::seq!(__impl r (), r, $($rest)*)
         },
@@ -214,6 +577,14 @@ macro_rules! seq {
         Ok($e)
     };
 
+    (__impl r $e:expr, $r:ident, if $($rest:tt)*) => {
+        $crate::seq!(__if r [$e, $r] [] $($rest)*)
+    };
+
+    (__impl r $e:expr, $r:ident, $name:ident : match $($rest:tt)*) => {
+        $crate::seq!(__match r [$e, $r] $name [] $($rest)*)
+    };
+
     (__impl r $e:expr, $r:ident, $name:ident : $expr:expr => $($rest:tt)*) => {
         {
             let $name = $crate::read($r, $expr)?;
@@ -228,6 +599,13 @@ macro_rules! seq {
         }
     };
 
+    (__impl r $e:expr, $r:ident, $name:ident = $expr:expr => $($rest:tt)*) => {
+        {
+            let $name = $expr;
+            $crate::seq!(__impl r $e, $r, $($rest)*)
+        }
+    };
+
     (__impl r $e:expr, $r:ident, $expr:expr => $($rest:tt)*) => {
         {
             let _: () = $crate::read($r, $expr)?;
@@ -237,9 +615,18 @@ macro_rules! seq {
 
     (__impl w $w:ident, ) => {};
 
+    (__impl w $w:ident, if $($rest:tt)*) => {
+        $crate::seq!(__if w [$w] [] $($rest)*);
+    };
+
+    (__impl w $w:ident, $name:ident : match $($rest:tt)*) => {
+        $crate::seq!(__match w [$w] $name [] $($rest)*);
+    };
+
     (__impl w $w:ident, $name:ident : $expr:expr => $($rest:tt)*) => {
         {
             $crate::write($w, $name, $expr)?;
+            let $name = $crate::seq!(__view $name);
             $crate::seq!(__impl w $w, $($rest)*);
         }
     };
@@ -248,6 +635,15 @@ macro_rules! seq {
         {
             let $name = &$def;
             $crate::write($w, $name, $expr)?;
+            let $name = $crate::seq!(__view $name);
+            $crate::seq!(__impl w $w, $($rest)*);
+        }
+    };
+
+    (__impl w $w:ident, $name:ident = $expr:expr => $($rest:tt)*) => {
+        {
+            #[allow(unused_variables)]
+            let $name = $expr;
             $crate::seq!(__impl w $w, $($rest)*);
         }
     };
@@ -258,11 +654,196 @@ macro_rules! seq {
             $crate::seq!(__impl w $w, $($rest)*);
         }
     };
+
+    // The condition of an `if` group is collected up to
+    // the block, then the names of the fields are collected.
+    (__if $m:ident $ctx:tt [$($c:tt)*] { $($en:tt)* } $($rest:tt)*) => {
+        $crate::seq!(__names $m $ctx [$($c)*] [] [$($en)*] [$($en)*] $($rest)*)
+    };
+
+    (__if $m:ident $ctx:tt [$($c:tt)*] $t:tt $($rest:tt)*) => {
+        $crate::seq!(__if $m $ctx [$($c)* $t] $($rest)*)
+    };
+
+    (__names $m:ident $ctx:tt $c:tt [$($n:ident)*] [$name:ident : $expr:expr => $($next:tt)*] $($rest:tt)*) => {
+        $crate::seq!(__names $m $ctx $c [$($n)* $name] [$($next)*] $($rest)*)
+    };
+
+    // Variables with a write default are locals of the group,
+    // since while writing they are computed from the default.
+    (__names $m:ident $ctx:tt $c:tt [$($n:ident)*] [$name:ident : $expr:expr, $def:expr => $($next:tt)*] $($rest:tt)*) => {
+        $crate::seq!(__names $m $ctx $c [$($n)*] [$($next)*] $($rest)*)
+    };
+
+    (__names $m:ident $ctx:tt $c:tt [$($n:ident)*] [$name:ident = $expr:expr => $($next:tt)*] $($rest:tt)*) => {
+        $crate::seq!(__names $m $ctx $c [$($n)* $name] [$($next)*] $($rest)*)
+    };
+
+    (__names $m:ident $ctx:tt $c:tt [$($n:ident)*] [$expr:expr => $($next:tt)*] $($rest:tt)*) => {
+        $crate::seq!(__names $m $ctx $c [$($n)*] [$($next)*] $($rest)*)
+    };
+
+    (__names r [$e:expr, $r:ident] [$($c:tt)*] [$($n:ident)*] [] [$($en:tt)*] $($rest:tt)*) => {
+        {
+            $crate::size::variable()?;
+            let ($($n,)*) = match $($c)* {
+                true => {
+                    let res: ::std::io::Result<_> = $crate::seq!(__impl r ($(Some($n),)*), $r, $($en)*);
+                    res?
+                },
+                false => ($($crate::seq!(__none $n),)*)
+            };
+            $crate::seq!(__impl r $e, $r, $($rest)*)
+        }
+    };
+
+    (__names w [$w:ident] [$($c:tt)*] [$($n:ident)*] [] [$($en:tt)*] $($rest:tt)*) => {
+        {
+            #[allow(unreachable_patterns)]
+            match ($($c)*, ($($n,)*)) {
+                (true, ($(Some($n),)*)) => { $crate::seq!(__impl w $w, $($en)*); },
+                (false, ($($crate::seq!(__none $n),)*)) => {},
+                _ => return Err(::std::io::Error::from($crate::BinError::ConditionMismatch))
+            }
+            $( let $n = $crate::seq!(__view $n); )*
+            $crate::seq!(__impl w $w, $($rest)*);
+        }
+    };
+
+    (__none $n:ident) => { None };
+
+    // The scrutinee of a `match` group is collected up to
+    // the arms, then each arm is split into patterns, 
+    // variant and entries.
+    (__match $m:ident $ctx:tt $name:ident [$($s:tt)*] { $($arms:tt)* } => $($rest:tt)*) => {
+        $crate::seq!(__arms $m $ctx $name [$($s)*] [] { $($arms)* } $($rest)*)
+    };
+
+    (__match $m:ident $ctx:tt $name:ident [$($s:tt)*] $t:tt $($rest:tt)*) => {
+        $crate::seq!(__match $m $ctx $name [$($s)* $t] $($rest)*)
+    };
+
+    (__arms $m:ident $ctx:tt $name:ident $s:tt [$($a:tt)*] { 
+        $($p:pat)|+ => $($v:ident)::+ { $($f:tt)* } { $($en:tt)* } $(, $($more:tt)*)? 
+    } $($rest:tt)*) => {
+        $crate::seq!(__arms $m $ctx $name $s [$($a)* [[$($p)|+] [$($v)::+ { $($f)* }] [$($en)*]]] { $($($more)*)? } $($rest)*)
+    };
+
+    (__arms $m:ident $ctx:tt $name:ident $s:tt [$($a:tt)*] { 
+        $($p:pat)|+ => $($v:ident)::+ ( $($f:tt)* ) { $($en:tt)* } $(, $($more:tt)*)? 
+    } $($rest:tt)*) => {
+        $crate::seq!(__arms $m $ctx $name $s [$($a)* [[$($p)|+] [$($v)::+ ( $($f)* )] [$($en)*]]] { $($($more)*)? } $($rest)*)
+    };
+
+    (__arms $m:ident $ctx:tt $name:ident $s:tt [$($a:tt)*] { 
+        $($p:pat)|+ => $($v:ident)::+ { $($en:tt)* } $(, $($more:tt)*)? 
+    } $($rest:tt)*) => {
+        $crate::seq!(__arms $m $ctx $name $s [$($a)* [[$($p)|+] [$($v)::+] [$($en)*]]] { $($($more)*)? } $($rest)*)
+    };
+
+    (__arms r [$e:expr, $r:ident] $name:ident [$($s:tt)*] [$([[$($p:tt)*] [$($h:tt)*] [$($en:tt)*]])*] { } $($rest:tt)*) => {
+        {
+            $crate::size::variable()?;
+            #[allow(unreachable_patterns)]
+            let $name = match $($s)* {
+                $( $($p)* => {
+                    let res: ::std::io::Result<_> = $crate::seq!(__impl r $($h)*, $r, $($en)*);
+                    res?
+                }, )*
+                _ => return Err(::std::io::Error::from($crate::BinError::UnknownVariant))
+            };
+            $crate::seq!(__impl r $e, $r, $($rest)*)
+        }
+    };
+
+    (__arms w [$w:ident] $name:ident [$($s:tt)*] [$([[$($p:tt)*] [$($h:tt)*] [$($en:tt)*]])*] { } $($rest:tt)*) => {
+        {
+            #[allow(unreachable_patterns)]
+            match ($($s)*, $name) {
+                $( ($($p)*, $($h)*) => { $crate::seq!(__impl w $w, $($en)*); }, )*
+                _ => return Err(::std::io::Error::from($crate::BinError::ConditionMismatch))
+            }
+            $crate::seq!(__impl w $w, $($rest)*);
+        }
+    };
+
+    (__view $name:ident) => {
+        {
+            #[allow(unused_imports)]
+            use $crate::macros::{ ViewCopy, ViewRef };
+            (&$crate::macros::View($name)).view()
+        }
+    };
+
+    ($ty:ident $($rest:tt)*) => {
+        $crate::seq!(__head $ty $($rest)*)
+    };
+
+    ((), $($rest:tt)*) => {
+        $crate::seq!(__head (), $($rest)*)
+    };
+}
+
+/// Reference to a value written by `seq!`.
+/// 
+/// `view()` returns a copy of the value if it's `Copy`,
+/// and the reference otherwise.
+pub struct View<'a, T>(pub &'a T);
+
+pub trait ViewCopy<T> {
+    fn view(&self) -> T;
+}
+
+impl<'a, T: Copy> ViewCopy<T> for View<'a, T> {
+    fn view(&self) -> T {
+        *self.0
+    }
+}
+
+pub trait ViewRef<'a, T> {
+    fn view(&self) -> &'a T;
+}
+
+impl<'a, T> ViewRef<'a, T> for &View<'a, T> {
+    fn view(&self) -> &'a T {
+        self.0
+    }
+}
+
+/// Operation performed by a parser built with `seq!(move ...)`.
+pub enum Op<'a, R: ?Sized, W: ?Sized, I> {
+    Read(&'a mut R),
+    Write(&'a mut W, &'a I)
+}
+
+/// Splits a single closure handling both operations 
+/// into a read/write tuple, cloning its captures.
+pub fn split<R: Read + ?Sized, W: Write + ?Sized, I, F>(f: F) 
+-> (impl ReadFn<R, I>, impl WriteFn<W, I>)
+where F: Fn(Op<'_, R, W, I>) -> io::Result<Option<I>> + Clone {
+
+    let (rf, wf) = (f.clone(), f);
+
+    (move |r: &mut R| {
+        match rf(Op::Read(r))? {
+            Some(i) => Ok(i),
+            None => unreachable!()
+        }
+    },
+    move |w: &mut W, i: &I| {
+        wf(Op::Write(w, i)).map(|_| ())
+    })
 }
 
 /// Macro used to remove boilerplate code
 /// from a function definition.
 /// 
+/// # Remarks
+/// The macro accepts a full function signature (doc attributes,
+/// multiple parameters, generics, lifetimes and where-clauses),
+/// and appends the `R: Read + ?Sized, W: Write + ?Sized` generics to it, so they 
+/// can be used in the where-clause.
+/// 
 /// # Examples
 /// ```
 /// use std::io::{ Read, Write };
@@ -271,7 +852,7 @@ macro_rules! seq {
 /// use bin_io::numbers::be_u8;
 /// 
 /// // Without boilerplate
-/// pub fn my_parser_1<R: Read, W: Write>() 
+/// pub fn my_parser_1<R: Read + ?Sized, W: Write + ?Sized>() 
 /// -> (impl ReadFn<R, u8>, impl WriteFn<W, u8>) {
 ///     be_u8()
 /// }
@@ -286,12 +867,317 @@ macro_rules! seq {
 /// );
 /// 
 /// ```
+/// Parameters, generics and where-clauses work as usual
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::{ boilerplate, count, read, ReadFn, WriteFn };
+/// use bin_io::numbers::le_u16;
+/// 
+/// boilerplate!(
+///     /// Reads a list of `n` items.
+///     pub fn list_of<T, Rf, Wf>(f: (Rf, Wf), n: usize) -> Vec<T> 
+///     where Rf: ReadFn<R, T>, Wf: WriteFn<W, T> {
+///         count(f, n)
+///     }
+/// );
+/// 
+/// let vec = vec![ 0x01, 0x00, 0x02, 0x00 ];
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// let list = read(&mut cursor, list_of(le_u16(), 2))
+///     .unwrap();
+/// 
+/// assert_eq!(list, vec![ 1, 2 ]);
+/// ```
 #[macro_export]
 macro_rules! boilerplate {
-    ($vis:vis fn $name:ident ( $($arg:ident : $ty:ty),* ) -> $ret:ty { $($tt:tt)* } ) => {
-        $vis fn $name <R: std::io::Read, W: std::io::Write> ( $( $arg : $ty )* ) 
-        -> (impl $crate::ReadFn<R, $ret>, impl $crate::WriteFn<W, $ret>) {
-            $($tt)*
+    ($(#[$meta:meta])* $vis:vis fn $name:ident < $($rest:tt)*) => {
+        $crate::boilerplate!(__gen [$(#[$meta])* $vis fn $name] [] [] $($rest)*);
+    };
+
+    ($(#[$meta:meta])* $vis:vis fn $name:ident $($rest:tt)*) => {
+        $crate::boilerplate!(__args [$(#[$meta])* $vis fn $name] [] $($rest)*);
+    };
+
+    // Generics are collected up to the matching `>`,
+    // the last list keeps track of the nesting.
+    (__gen $h:tt [$($g:tt)*] [] $(,)? > $($rest:tt)*) => {
+        $crate::boilerplate!(__args $h [$($g)* ,] $($rest)*);
+    };
+
+    (__gen $h:tt [$($g:tt)*] [x] >> $($rest:tt)*) => {
+        $crate::boilerplate!(__args $h [$($g)* > ,] $($rest)*);
+    };
+
+    (__gen $h:tt [$($g:tt)*] [x $($d:tt)*] > $($rest:tt)*) => {
+        $crate::boilerplate!(__gen $h [$($g)* >] [$($d)*] $($rest)*);
+    };
+
+    (__gen $h:tt [$($g:tt)*] [x x $($d:tt)*] >> $($rest:tt)*) => {
+        $crate::boilerplate!(__gen $h [$($g)* >>] [$($d)*] $($rest)*);
+    };
+
+    (__gen $h:tt [$($g:tt)*] [$($d:tt)*] < $($rest:tt)*) => {
+        $crate::boilerplate!(__gen $h [$($g)* <] [x $($d)*] $($rest)*);
+    };
+
+    (__gen $h:tt [$($g:tt)*] [$($d:tt)*] $t:tt $($rest:tt)*) => {
+        $crate::boilerplate!(__gen $h [$($g)* $t] [$($d)*] $($rest)*);
+    };
+
+    (__args $h:tt $g:tt ( $($arg:tt)* ) -> $ret:ty { $($body:tt)* }) => {
+        $crate::boilerplate!(__emit $h $g [$($arg)*] [$ret] [] { $($body)* });
+    };
+
+    (__args $h:tt $g:tt ( $($arg:tt)* ) -> $ret:ty where $($rest:tt)*) => {
+        $crate::boilerplate!(__where $h $g [$($arg)*] [$ret] [] $($rest)*);
+    };
+
+    (__where $h:tt $g:tt $a:tt $ret:tt [$($w:tt)*] { $($body:tt)* }) => {
+        $crate::boilerplate!(__emit $h $g $a $ret [$($w)*] { $($body)* });
+    };
+
+    (__where $h:tt $g:tt $a:tt $ret:tt [$($w:tt)*] $t:tt $($rest:tt)*) => {
+        $crate::boilerplate!(__where $h $g $a $ret [$($w)* $t] $($rest)*);
+    };
+
+    (__emit [$($h:tt)*] [$($g:tt)*] [$($arg:tt)*] [$ret:ty] [$($w:tt)*] { $($body:tt)* }) => {
+        $($h)* < $($g)* R: std::io::Read + ?Sized, W: std::io::Write + ?Sized > ( $($arg)* ) 
+        -> (impl $crate::ReadFn<R, $ret>, impl $crate::WriteFn<W, $ret>)
+        where $($w)* {
+            $($body)*
         }
     };
-}
\ No newline at end of file
+}
+
+/// Macro used to define a C-like enum together
+/// with its mapping to an integer type.
+/// 
+/// The macro generates the enum and the conversions 
+/// needed by `try_cast`. Without a catch-all variant, 
+/// reading an unknown value fails; with a catch-all 
+/// variant (`_ => Unknown`) the unknown value is kept 
+/// so that it can be written back.
+/// 
+/// # Remarks
+/// `try_cast` clones the value while writing, so the 
+/// enum must implement `Clone`.
+/// 
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::{ bin_enum, read, write, try_cast };
+/// use bin_io::numbers::be_u8;
+/// 
+/// bin_enum! {
+///     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+///     pub enum Kind: u8 {
+///         File = 0x01,
+///         Dir = 0x02,
+///     }
+/// }
+/// 
+/// bin_enum! {
+///     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+///     pub enum Opcode: u8 {
+///         Nop = 0x00,
+///         Jump = 0x10,
+///         _ => Unknown
+///     }
+/// }
+/// 
+/// let vec = vec![ 0x02, 0x03, 0x10, 0x42 ];
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// let kind: Kind = read(&mut cursor, try_cast(be_u8()))
+///     .unwrap();
+/// 
+/// assert_eq!(kind, Kind::Dir);
+/// 
+/// // 0x03 is not a valid Kind
+/// let err = read::<_, _, _, Kind>(&mut cursor, try_cast(be_u8()));
+/// 
+/// assert!(err.is_err());
+/// 
+/// let a: Opcode = read(&mut cursor, try_cast(be_u8()))
+///     .unwrap();
+/// let b: Opcode = read(&mut cursor, try_cast(be_u8()))
+///     .unwrap();
+/// 
+/// assert_eq!(a, Opcode::Jump);
+/// assert_eq!(b, Opcode::Unknown(0x42));
+/// 
+/// let mut cursor = Cursor::new(Vec::new());
+/// 
+/// write(&mut cursor, &Opcode::Unknown(0x42), try_cast(be_u8()))
+///     .unwrap();
+/// 
+/// assert_eq!(cursor.get_ref(), &[ 0x42 ]);
+/// ```
+#[macro_export]
+macro_rules! bin_enum {
+    ($(#[$meta:meta])* $vis:vis enum $name:ident : $repr:ty {
+        $( $(#[$vmeta:meta])* $var:ident = $val:expr, )*
+        _ => $unk:ident $(,)?
+    }) => {
+        $(#[$meta])*
+        $vis enum $name {
+            $( $(#[$vmeta])* $var, )*
+            $unk($repr)
+        }
+
+        impl ::std::convert::From<$repr> for $name {
+            fn from(v: $repr) -> Self {
+                match v {
+                    $( v if v == $val => $name::$var, )*
+                    v => $name::$unk(v)
+                }
+            }
+        }
+
+        impl ::std::convert::From<$name> for $repr {
+            fn from(v: $name) -> Self {
+                match v {
+                    $( $name::$var => $val, )*
+                    $name::$unk(v) => v
+                }
+            }
+        }
+    };
+
+    ($(#[$meta:meta])* $vis:vis enum $name:ident : $repr:ty {
+        $( $(#[$vmeta:meta])* $var:ident = $val:expr ),* $(,)?
+    }) => {
+        $(#[$meta])*
+        $vis enum $name {
+            $( $(#[$vmeta])* $var ),*
+        }
+
+        impl ::std::convert::TryFrom<$repr> for $name {
+            type Error = $crate::BinError;
+
+            fn try_from(v: $repr) -> ::std::result::Result<Self, Self::Error> {
+                match v {
+                    $( v if v == $val => Ok($name::$var), )*
+                    _ => Err($crate::BinError::UnknownVariant)
+                }
+            }
+        }
+
+        impl ::std::convert::From<$name> for $repr {
+            fn from(v: $name) -> Self {
+                match v {
+                    $( $name::$var => $val ),*
+                }
+            }
+        }
+    };
+}
+
+/// Reads/Writes a value protected by a checksum.
+/// 
+/// The syntax is `checksummed!(f, c, a)`: `f` is the parser 
+/// of the protected value, `c` the parser of the checksum 
+/// and `a` the `Checksum` algorithm.
+/// 
+/// This is a shorthand for `checksum::checksummed`, which 
+/// takes `f` as closures over `dyn Read` and `dyn Write`: 
+/// the macro builds them, calling `read`/`write` with `f`.
+/// 
+/// # Remarks
+/// `f` is built again on every call. Like `seq!`, prefix 
+/// the arguments with `move` to capture the arguments of 
+/// a parser function.
+/// 
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::checksum::Sum8;
+/// use bin_io::numbers::{ be_u8, be_u16 };
+/// use bin_io::{ checksummed, read, write };
+/// 
+/// let vec = Vec::new();
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// write(&mut cursor, &0x0102, checksummed!(be_u16(), be_u8(), Sum8::default()))
+///     .unwrap();
+/// 
+/// assert_eq!(cursor.get_ref(), &vec![ 0x01, 0x02, 0x03 ]);
+/// 
+/// cursor.set_position(0);
+/// 
+/// let value = read(&mut cursor, checksummed!(be_u16(), be_u8(), Sum8::default()))
+///     .unwrap();
+/// 
+/// assert_eq!(value, 0x0102);
+/// ```
+#[macro_export]
+macro_rules! checksummed {
+    (move $($rest:tt)*) => {
+        $crate::checksummed!(__impl [move] $($rest)*)
+    };
+
+    (__impl [$($mv:tt)*] $f:expr, $c:expr, $a:expr $(,)?) => {
+        $crate::checksum::checksummed(
+            ($($mv)* |r: &mut dyn ::std::io::Read| $crate::read(r, $f),
+            $($mv)* |w: &mut dyn ::std::io::Write, i: &_| $crate::write(w, i, $f)),
+            $c,
+            $a
+        )
+    };
+
+    ($($rest:tt)*) => {
+        $crate::checksummed!(__impl [] $($rest)*)
+    };
+}
+
+/// Erases the type of a parser.
+/// 
+/// This is a shorthand for `dynamic::boxed`, which 
+/// takes closures over `dyn Read` and `dyn Write`: 
+/// the macro builds them, calling `read`/`write` with 
+/// the parser.
+/// 
+/// # Remarks
+/// The parser is built again on every call. Like `seq!`, 
+/// prefix it with `move` to capture the arguments of a 
+/// parser function.
+/// 
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::dynamic::DynParser;
+/// use bin_io::numbers::{ be_u16, le_u16 };
+/// use bin_io::{ boxed, count };
+/// 
+/// // Both have the same type
+/// let parsers: Vec<DynParser<Vec<u16>>> = vec![
+///     boxed!(count(be_u16(), 1)),
+///     boxed!(count(le_u16(), 2))
+/// ];
+/// 
+/// let vec = vec![ 0x01, 0x00, 0x02, 0x00 ];
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// let val = (parsers[1].0)(&mut cursor)
+///     .unwrap();
+/// 
+/// assert_eq!(val, vec![ 1, 2 ]);
+/// ```
+#[macro_export]
+macro_rules! boxed {
+    (move $f:expr $(,)?) => {
+        $crate::boxed!(__impl [move] $f)
+    };
+
+    (__impl [$($mv:tt)*] $f:expr) => {
+        $crate::dynamic::boxed((
+            $($mv)* |r: &mut (dyn ::std::io::Read + '_)| $crate::read(r, $f),
+            $($mv)* |w: &mut (dyn ::std::io::Write + '_), i: &_| $crate::write(w, i, $f)
+        ))
+    };
+
+    ($f:expr $(,)?) => {
+        $crate::boxed!(__impl [] $f)
+    };
+}
@@ -23,47 +23,1117 @@
 //! 
 //! assert_eq!(val, 1.5);
 //! ```
+//! 
+//! Large blocks of numbers can be read/written with the
+//! `_array_of` variants, which read/write whole blocks of 
+//! bytes and only swap them when the endianness differs 
+//! from the host's one.
+//! ```
+//! use std::io::Cursor;
+//! use bin_io::numbers::{ le_u16_array_of };
+//! use bin_io::{ read, write };
+//! 
+//! let vec = Vec::new();
+//! let mut cursor = Cursor::new(vec);
+//! 
+//! write(&mut cursor, &vec![ 1, 2, 3 ], le_u16_array_of(3))
+//!     .unwrap();
+//! 
+//! assert_eq!(cursor.get_ref(), &[ 0x01, 0x00, 0x02, 0x00, 0x03, 0x00 ]);
+//! 
+//! cursor.set_position(0);
+//! 
+//! let val = read(&mut cursor, le_u16_array_of(3))
+//!     .unwrap();
+//! 
+//! assert_eq!(val, &[ 1, 2, 3 ]);
+//! 
+//! // Writing a vec of the wrong length fails
+//! let err = write(&mut cursor, &vec![ 1, 2 ], le_u16_array_of(3));
+//! 
+//! assert!(err.is_err());
+//! 
+//! // A corrupt length fails once the data ends,
+//! // without allocating the whole vec upfront
+//! cursor.set_position(0);
+//! 
+//! let err = read(&mut cursor, le_u16_array_of(1 << 30));
+//! 
+//! assert!(err.is_err());
+//! ```
+//! 
+//! Every parser comes in a big endian (`be_`), little endian (`le_`)
+//! and native endian (`ne_`) variant, the latter is useful for data
+//! produced by the same machine (like memory dumps).
+//! ```
+//! use std::io::Cursor;
+//! use bin_io::numbers::{ ne_u32, be_u128 };
+//! use bin_io::read;
+//! 
+//! let vec = 0xdeadbeefu32.to_ne_bytes().to_vec();
+//! let mut cursor = Cursor::new(vec);
+//! 
+//! let val = read(&mut cursor, ne_u32())
+//!     .unwrap();
+//! 
+//! assert_eq!(val, 0xdeadbeef);
+//! 
+//! // An UUID
+//! let vec = vec![ 
+//!     0x12, 0x3e, 0x45, 0x67, 0xe8, 0x9b, 0x12, 0xd3, 
+//!     0xa4, 0x56, 0x42, 0x66, 0x14, 0x17, 0x40, 0x00 
+//! ];
+//! let mut cursor = Cursor::new(vec);
+//! 
+//! let val = read(&mut cursor, be_u128())
+//!     .unwrap();
+//! 
+//! assert_eq!(val, 0x123e4567_e89b_12d3_a456_426614174000);
+//! ```
+//! 
+//! Half precision (`f16`) and brain floating point (`bf16`) numbers
+//! are read/written as `f32`, rounding to nearest when writing. With 
+//! the `half` feature the `_half_` variants use the types of the `half`
+//! crate instead.
+//! ```
+//! use std::io::Cursor;
+//! use bin_io::numbers::{ le_f16, be_bf16 };
+//! use bin_io::{ read, write };
+//! 
+//! let vec = Vec::new();
+//! let mut cursor = Cursor::new(vec);
+//! 
+//! write(&mut cursor, &-1.5, le_f16())
+//!     .unwrap();
+//! write(&mut cursor, &3.140625, be_bf16())
+//!     .unwrap();
+//! 
+//! assert_eq!(cursor.get_ref(), &[ 0x00, 0xbe, 0x40, 0x49 ]);
+//! 
+//! cursor.set_position(0);
+//! 
+//! assert_eq!(read(&mut cursor, le_f16()).unwrap(), -1.5);
+//! assert_eq!(read(&mut cursor, be_bf16()).unwrap(), 3.140625);
+//! ```
+//! 
+//! 24 and 48 bit integers are read/written as the next 
+//! bigger type, writing a value that doesn't fit fails.
+//! ```
+//! use std::io::Cursor;
+//! use bin_io::numbers::{ be_u24 };
+//! use bin_io::write;
+//! 
+//! let vec = Vec::new();
+//! let mut cursor = Cursor::new(vec);
+//! 
+//! write(&mut cursor, &0xabcdef, be_u24())
+//!     .unwrap();
+//! 
+//! assert_eq!(cursor.get_ref(), &[ 0xab, 0xcd, 0xef ]);
+//! 
+//! let err = write(&mut cursor, &0x1000000, be_u24());
+//! 
+//! assert!(err.is_err());
+//! ```
+
+use crate::{ ReadFn, WriteFn, BinError, MAX_PREALLOC, size };
+use std::io::{ self, Read, Write, Error };
+use std::convert::TryFrom;
+
+use byteorder::{ ReadBytesExt, WriteBytesExt, ByteOrder, BigEndian, LittleEndian, NativeEndian };
+
+/// Byte order, used by parsers that pick 
+/// the endianness at runtime.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Endian {
+    Big,
+    Little
+}
+
+fn write_uint<B: ByteOrder, W: Write + ?Sized>(w: &mut W, v: u64, nbytes: usize) 
+-> io::Result<()> {
+    match nbytes == 8 || v >> (nbytes * 8) == 0 {
+        true => w.write_uint::<B>(v, nbytes),
+        false => Err(Error::from(BinError::CastFail))
+    }
+}
+
+fn write_int<B: ByteOrder, W: Write + ?Sized>(w: &mut W, v: i64, nbytes: usize) 
+-> io::Result<()> {
+    let bits = nbytes * 8;
+    match bits == 64 || (v >> (bits - 1) == 0 || v >> (bits - 1) == -1) {
+        true => w.write_int::<B>(v, nbytes),
+        false => Err(Error::from(BinError::CastFail))
+    }
+}
+
+fn f16_to_f32(v: u16) -> f32 {
+    let sign = ((v & 0x8000) as u32) << 16;
+    let exp = ((v >> 10) & 0x1f) as u32;
+    let mant = (v & 0x3ff) as u32;
+
+    match (exp, mant) {
+        (0, 0) => f32::from_bits(sign),
+        // Subnormals are exactly representable as normal f32
+        (0, _) => match sign {
+            0 => mant as f32 / (1 << 24) as f32,
+            _ => -(mant as f32) / (1 << 24) as f32
+        },
+        // Infinity and NaN, keeping the payload
+        (0x1f, _) => f32::from_bits(sign | 0x7f80_0000 | (mant << 13)),
+        _ => f32::from_bits(sign | ((exp + 127 - 15) << 23) | (mant << 13))
+    }
+}
+
+fn f32_to_f16(v: f32) -> u16 {
+    let x = v.to_bits();
+    let sign = ((x >> 16) & 0x8000) as u16;
+    let exp = ((x >> 23) & 0xff) as i32;
+    let mant = x & 0x7f_ffff;
+
+    // Rounds to nearest, ties to even
+    let round = |m: u32, shift: u32| {
+        let (hm, rem, half) = (m >> shift, m & ((1 << shift) - 1), 1 << (shift - 1));
+        match rem > half || (rem == half && hm & 1 == 1) {
+            true => hm + 1,
+            false => hm
+        }
+    };
+
+    let e = exp - 127 + 15;
+    match (exp, e) {
+        // NaN, keeping the upper bits of the payload
+        // but making sure it doesn't become infinity
+        (0xff, _) if mant != 0 => match (mant >> 13) as u16 {
+            0 => sign | 0x7e00,
+            m => sign | 0x7c00 | m
+        },
+        (0xff, _) => sign | 0x7c00,
+        (_, 0x1f..=i32::MAX) => sign | 0x7c00,
+        (_, i32::MIN..=-11) => sign,
+        // Subnormals, the rounding might produce the smallest normal
+        (_, -10..=0) => sign | round(mant | 0x80_0000, (14 - e) as u32) as u16,
+        // The rounding might carry into the exponent (even up to infinity)
+        _ => sign | round(((e as u32) << 23) | mant, 13) as u16
+    }
+}
+
+fn bf16_to_f32(v: u16) -> f32 {
+    f32::from_bits((v as u32) << 16)
+}
+
+fn f32_to_bf16(v: f32) -> u16 {
+    let x = v.to_bits();
 
-use crate::{ ReadFn, WriteFn };
-use std::io::{ Read, Write };
+    match v.is_nan() {
+        // NaN, making sure it doesn't become infinity
+        true => match (x >> 16) as u16 {
+            m if m & 0x7f == 0 => m | 0x40,
+            m => m
+        },
+        // Rounds to nearest, ties to even
+        false => ((x as u64 + 0x7fff + ((x >> 16) & 1) as u64) >> 16) as u16
+    }
+}
+
+/// Conversion of the values made up while 
+/// running `fixed_size`.
+trait Fake {
+    fn fake(v: u8) -> Self;
+}
+
+macro_rules! fake_impl {
+    ($($ty:ty),*) => {
+        $(
+            impl Fake for $ty {
+                fn fake(v: u8) -> Self {
+                    v as $ty
+                }
+            }
+        )*
+    };
+}
+
+fake_impl!(u8, i8, u16, i16, u32, i32, u64, i64, u128, i128, f32, f64);
+
+#[cfg(feature = "half")]
+impl Fake for half::f16 {
+    fn fake(v: u8) -> Self {
+        half::f16::from(v)
+    }
+}
 
-use byteorder::{ ReadBytesExt, WriteBytesExt, BigEndian, LittleEndian };
+#[cfg(feature = "half")]
+impl Fake for half::bf16 {
+    fn fake(v: u8) -> Self {
+        half::bf16::from(v)
+    }
+}
 
 macro_rules! auto_impl {
-    ($name:ident, $ty:ty, $r:ident, $w:ident, $v:ident, $read:expr, $write:expr) => {
-        pub fn $name<R: Read, W: Write>() 
+    ($name:ident, $ty:ty, $size:expr, $r:ident, $w:ident, $v:ident, $read:expr, $write:expr) => {
+        pub fn $name<R: Read + ?Sized, W: Write + ?Sized>() 
         -> (impl ReadFn<R, $ty>, impl WriteFn<W, $ty>) {
         
-            (|$r: &mut R| 
-                $read,
+            (|$r: &mut R| match size::fixed($size) {
+                Some(v) => Ok(Fake::fake(v)),
+                None => $read
+            },
             |$w: &mut W, $v: &$ty| 
                 $write)
         }
     };
 }
 
-auto_impl!(be_u8, u8, r, w, v, r.read_u8(), w.write_u8(*v));
-auto_impl!(be_i8, i8, r, w, v, r.read_i8(), w.write_i8(*v));
-auto_impl!(le_u8, u8, r, w, v, r.read_u8(), w.write_u8(*v));
-auto_impl!(le_i8, i8, r, w, v, r.read_i8(), w.write_i8(*v));
+/// Marker for the numbers whose bytes can be
+/// viewed directly.
+/// 
+/// # Safety
+/// The type must have no padding bytes.
+unsafe trait Plain: Copy {}
 
-auto_impl!(be_u16, u16, r, w, v, r.read_u16::<BigEndian>(), w.write_u16::<BigEndian>(*v));
-auto_impl!(be_i16, i16, r, w, v, r.read_i16::<BigEndian>(), w.write_i16::<BigEndian>(*v));
-auto_impl!(le_u16, u16, r, w, v, r.read_u16::<LittleEndian>(), w.write_u16::<LittleEndian>(*v));
-auto_impl!(le_i16, i16, r, w, v, r.read_i16::<LittleEndian>(), w.write_i16::<LittleEndian>(*v));
+unsafe impl Plain for i8 {}
+unsafe impl Plain for u16 {}
+unsafe impl Plain for i16 {}
+unsafe impl Plain for u32 {}
+unsafe impl Plain for i32 {}
+unsafe impl Plain for u64 {}
+unsafe impl Plain for i64 {}
+unsafe impl Plain for u128 {}
+unsafe impl Plain for i128 {}
+unsafe impl Plain for f32 {}
+unsafe impl Plain for f64 {}
 
-auto_impl!(be_u32, u32, r, w, v, r.read_u32::<BigEndian>(), w.write_u32::<BigEndian>(*v));
-auto_impl!(be_i32, i32, r, w, v, r.read_i32::<BigEndian>(), w.write_i32::<BigEndian>(*v));
-auto_impl!(le_u32, u32, r, w, v, r.read_u32::<LittleEndian>(), w.write_u32::<LittleEndian>(*v));
-auto_impl!(le_i32, i32, r, w, v, r.read_i32::<LittleEndian>(), w.write_i32::<LittleEndian>(*v));
+/// Returns the bytes of a slice of numbers, in host order.
+fn ne_bytes<T: Plain>(v: &[T]) -> &[u8] {
+    // SAFETY: `T` has no padding, so every byte of the slice 
+    // is initialized, and `u8` has no alignment requirement.
+    unsafe { std::slice::from_raw_parts(v.as_ptr() as *const u8, std::mem::size_of_val(v)) }
+}
 
-auto_impl!(be_u64, u64, r, w, v, r.read_u64::<BigEndian>(), w.write_u64::<BigEndian>(*v));
-auto_impl!(be_i64, i64, r, w, v, r.read_i64::<BigEndian>(), w.write_i64::<BigEndian>(*v));
-auto_impl!(le_u64, u64, r, w, v, r.read_u64::<LittleEndian>(), w.write_u64::<LittleEndian>(*v));
-auto_impl!(le_i64, i64, r, w, v, r.read_i64::<LittleEndian>(), w.write_i64::<LittleEndian>(*v));
+macro_rules! array_impl {
+    ($name:ident, $ty:ty, $r:ident, $w:ident, $v:ident, $read:expr, $write:expr) => {
+        pub fn $name<R: Read + ?Sized, W: Write + ?Sized>(n: usize) 
+        -> (impl ReadFn<R, Vec<$ty>>, impl WriteFn<W, Vec<$ty>>) {
 
-auto_impl!(be_f32, f32, r, w, v, r.read_f32::<BigEndian>(), w.write_f32::<BigEndian>(*v));
-auto_impl!(le_f32, f32, r, w, v, r.read_f32::<LittleEndian>(), w.write_f32::<LittleEndian>(*v));
+            (move |$r: &mut R| {
+                // The length might come from untrusted data, 
+                // so the vec grows a block at a time
+                let block = MAX_PREALLOC / std::mem::size_of::<$ty>();
+                let mut vec: Vec<$ty> = Vec::new();
 
-auto_impl!(be_f64, f64, r, w, v, r.read_f64::<BigEndian>(), w.write_f64::<BigEndian>(*v));
-auto_impl!(le_f64, f64, r, w, v, r.read_f64::<LittleEndian>(), w.write_f64::<LittleEndian>(*v));
\ No newline at end of file
+                while vec.len() < n {
+                    let start = vec.len();
+                    vec.resize(n.min(start + block), 0 as $ty);
+
+                    let $v = &mut vec[start..];
+                    $read?;
+                }
+
+                Ok(vec)
+            },
+            move |$w: &mut W, $v: &Vec<$ty>| {
+                match $v.len() == n {
+                    true => $write,
+                    false => Err(Error::from(BinError::LengthMismatch))
+                }
+            })
+        }
+    };
+
+    ($name:ident, $ty:ty, $read_into:ident, $write_into:ident, $endian:ident) => {
+        array_impl!($name, $ty, r, w, v, 
+            r.$read_into::<$endian>(&mut v[..]), 
+            match $endian::read_u16(&[ 0, 1 ]) == u16::from_ne_bytes([ 0, 1 ]) {
+                true => w.write_all(ne_bytes(&v[..])),
+                false => {
+                    let block = MAX_PREALLOC / std::mem::size_of::<$ty>();
+                    let mut buf = vec![0; std::mem::size_of_val(&v[..v.len().min(block)])];
+
+                    for c in v.chunks(block) {
+                        let buf = &mut buf[..std::mem::size_of_val(c)];
+                        $endian::$write_into(c, buf);
+                        w.write_all(buf)?;
+                    }
+
+                    Ok(())
+                }
+            });
+    };
+}
+
+auto_impl!(be_u8, u8, 1, r, w, v, r.read_u8(), w.write_u8(*v));
+auto_impl!(be_i8, i8, 1, r, w, v, r.read_i8(), w.write_i8(*v));
+auto_impl!(le_u8, u8, 1, r, w, v, r.read_u8(), w.write_u8(*v));
+auto_impl!(le_i8, i8, 1, r, w, v, r.read_i8(), w.write_i8(*v));
+auto_impl!(ne_u8, u8, 1, r, w, v, r.read_u8(), w.write_u8(*v));
+auto_impl!(ne_i8, i8, 1, r, w, v, r.read_i8(), w.write_i8(*v));
+
+auto_impl!(be_u16, u16, 2, r, w, v, r.read_u16::<BigEndian>(), w.write_u16::<BigEndian>(*v));
+auto_impl!(be_i16, i16, 2, r, w, v, r.read_i16::<BigEndian>(), w.write_i16::<BigEndian>(*v));
+auto_impl!(le_u16, u16, 2, r, w, v, r.read_u16::<LittleEndian>(), w.write_u16::<LittleEndian>(*v));
+auto_impl!(le_i16, i16, 2, r, w, v, r.read_i16::<LittleEndian>(), w.write_i16::<LittleEndian>(*v));
+auto_impl!(ne_u16, u16, 2, r, w, v, r.read_u16::<NativeEndian>(), w.write_u16::<NativeEndian>(*v));
+auto_impl!(ne_i16, i16, 2, r, w, v, r.read_i16::<NativeEndian>(), w.write_i16::<NativeEndian>(*v));
+
+auto_impl!(be_u32, u32, 4, r, w, v, r.read_u32::<BigEndian>(), w.write_u32::<BigEndian>(*v));
+auto_impl!(be_i32, i32, 4, r, w, v, r.read_i32::<BigEndian>(), w.write_i32::<BigEndian>(*v));
+auto_impl!(le_u32, u32, 4, r, w, v, r.read_u32::<LittleEndian>(), w.write_u32::<LittleEndian>(*v));
+auto_impl!(le_i32, i32, 4, r, w, v, r.read_i32::<LittleEndian>(), w.write_i32::<LittleEndian>(*v));
+auto_impl!(ne_u32, u32, 4, r, w, v, r.read_u32::<NativeEndian>(), w.write_u32::<NativeEndian>(*v));
+auto_impl!(ne_i32, i32, 4, r, w, v, r.read_i32::<NativeEndian>(), w.write_i32::<NativeEndian>(*v));
+
+auto_impl!(be_u64, u64, 8, r, w, v, r.read_u64::<BigEndian>(), w.write_u64::<BigEndian>(*v));
+auto_impl!(be_i64, i64, 8, r, w, v, r.read_i64::<BigEndian>(), w.write_i64::<BigEndian>(*v));
+auto_impl!(le_u64, u64, 8, r, w, v, r.read_u64::<LittleEndian>(), w.write_u64::<LittleEndian>(*v));
+auto_impl!(le_i64, i64, 8, r, w, v, r.read_i64::<LittleEndian>(), w.write_i64::<LittleEndian>(*v));
+auto_impl!(ne_u64, u64, 8, r, w, v, r.read_u64::<NativeEndian>(), w.write_u64::<NativeEndian>(*v));
+auto_impl!(ne_i64, i64, 8, r, w, v, r.read_i64::<NativeEndian>(), w.write_i64::<NativeEndian>(*v));
+
+auto_impl!(be_u128, u128, 16, r, w, v, r.read_u128::<BigEndian>(), w.write_u128::<BigEndian>(*v));
+auto_impl!(be_i128, i128, 16, r, w, v, r.read_i128::<BigEndian>(), w.write_i128::<BigEndian>(*v));
+auto_impl!(le_u128, u128, 16, r, w, v, r.read_u128::<LittleEndian>(), w.write_u128::<LittleEndian>(*v));
+auto_impl!(le_i128, i128, 16, r, w, v, r.read_i128::<LittleEndian>(), w.write_i128::<LittleEndian>(*v));
+auto_impl!(ne_u128, u128, 16, r, w, v, r.read_u128::<NativeEndian>(), w.write_u128::<NativeEndian>(*v));
+auto_impl!(ne_i128, i128, 16, r, w, v, r.read_i128::<NativeEndian>(), w.write_i128::<NativeEndian>(*v));
+
+auto_impl!(be_f32, f32, 4, r, w, v, r.read_f32::<BigEndian>(), w.write_f32::<BigEndian>(*v));
+auto_impl!(le_f32, f32, 4, r, w, v, r.read_f32::<LittleEndian>(), w.write_f32::<LittleEndian>(*v));
+auto_impl!(ne_f32, f32, 4, r, w, v, r.read_f32::<NativeEndian>(), w.write_f32::<NativeEndian>(*v));
+
+auto_impl!(be_f64, f64, 8, r, w, v, r.read_f64::<BigEndian>(), w.write_f64::<BigEndian>(*v));
+auto_impl!(le_f64, f64, 8, r, w, v, r.read_f64::<LittleEndian>(), w.write_f64::<LittleEndian>(*v));
+auto_impl!(ne_f64, f64, 8, r, w, v, r.read_f64::<NativeEndian>(), w.write_f64::<NativeEndian>(*v));
+
+auto_impl!(be_f16, f32, 2, r, w, v, r.read_u16::<BigEndian>().map(f16_to_f32), w.write_u16::<BigEndian>(f32_to_f16(*v)));
+auto_impl!(le_f16, f32, 2, r, w, v, r.read_u16::<LittleEndian>().map(f16_to_f32), w.write_u16::<LittleEndian>(f32_to_f16(*v)));
+auto_impl!(ne_f16, f32, 2, r, w, v, r.read_u16::<NativeEndian>().map(f16_to_f32), w.write_u16::<NativeEndian>(f32_to_f16(*v)));
+
+auto_impl!(be_bf16, f32, 2, r, w, v, r.read_u16::<BigEndian>().map(bf16_to_f32), w.write_u16::<BigEndian>(f32_to_bf16(*v)));
+auto_impl!(le_bf16, f32, 2, r, w, v, r.read_u16::<LittleEndian>().map(bf16_to_f32), w.write_u16::<LittleEndian>(f32_to_bf16(*v)));
+auto_impl!(ne_bf16, f32, 2, r, w, v, r.read_u16::<NativeEndian>().map(bf16_to_f32), w.write_u16::<NativeEndian>(f32_to_bf16(*v)));
+
+#[cfg(feature = "half")]
+auto_impl!(be_half_f16, half::f16, 2, r, w, v, r.read_u16::<BigEndian>().map(half::f16::from_bits), w.write_u16::<BigEndian>(v.to_bits()));
+#[cfg(feature = "half")]
+auto_impl!(le_half_f16, half::f16, 2, r, w, v, r.read_u16::<LittleEndian>().map(half::f16::from_bits), w.write_u16::<LittleEndian>(v.to_bits()));
+#[cfg(feature = "half")]
+auto_impl!(ne_half_f16, half::f16, 2, r, w, v, r.read_u16::<NativeEndian>().map(half::f16::from_bits), w.write_u16::<NativeEndian>(v.to_bits()));
+
+#[cfg(feature = "half")]
+auto_impl!(be_half_bf16, half::bf16, 2, r, w, v, r.read_u16::<BigEndian>().map(half::bf16::from_bits), w.write_u16::<BigEndian>(v.to_bits()));
+#[cfg(feature = "half")]
+auto_impl!(le_half_bf16, half::bf16, 2, r, w, v, r.read_u16::<LittleEndian>().map(half::bf16::from_bits), w.write_u16::<LittleEndian>(v.to_bits()));
+#[cfg(feature = "half")]
+auto_impl!(ne_half_bf16, half::bf16, 2, r, w, v, r.read_u16::<NativeEndian>().map(half::bf16::from_bits), w.write_u16::<NativeEndian>(v.to_bits()));
+
+auto_impl!(be_u24, u32, 3, r, w, v, r.read_u24::<BigEndian>(), write_uint::<BigEndian, _>(w, *v as u64, 3));
+auto_impl!(be_i24, i32, 3, r, w, v, r.read_i24::<BigEndian>(), write_int::<BigEndian, _>(w, *v as i64, 3));
+auto_impl!(le_u24, u32, 3, r, w, v, r.read_u24::<LittleEndian>(), write_uint::<LittleEndian, _>(w, *v as u64, 3));
+auto_impl!(le_i24, i32, 3, r, w, v, r.read_i24::<LittleEndian>(), write_int::<LittleEndian, _>(w, *v as i64, 3));
+auto_impl!(ne_u24, u32, 3, r, w, v, r.read_u24::<NativeEndian>(), write_uint::<NativeEndian, _>(w, *v as u64, 3));
+auto_impl!(ne_i24, i32, 3, r, w, v, r.read_i24::<NativeEndian>(), write_int::<NativeEndian, _>(w, *v as i64, 3));
+
+auto_impl!(be_u48, u64, 6, r, w, v, r.read_u48::<BigEndian>(), write_uint::<BigEndian, _>(w, *v, 6));
+auto_impl!(be_i48, i64, 6, r, w, v, r.read_i48::<BigEndian>(), write_int::<BigEndian, _>(w, *v, 6));
+auto_impl!(le_u48, u64, 6, r, w, v, r.read_u48::<LittleEndian>(), write_uint::<LittleEndian, _>(w, *v, 6));
+auto_impl!(le_i48, i64, 6, r, w, v, r.read_i48::<LittleEndian>(), write_int::<LittleEndian, _>(w, *v, 6));
+auto_impl!(ne_u48, u64, 6, r, w, v, r.read_u48::<NativeEndian>(), write_uint::<NativeEndian, _>(w, *v, 6));
+auto_impl!(ne_i48, i64, 6, r, w, v, r.read_i48::<NativeEndian>(), write_int::<NativeEndian, _>(w, *v, 6));
+
+array_impl!(be_u8_array_of, u8, r, w, v, r.read_exact(&mut v[..]), w.write_all(&v[..]));
+array_impl!(be_i8_array_of, i8, r, w, v, r.read_i8_into(&mut v[..]), w.write_all(ne_bytes(&v[..])));
+array_impl!(le_u8_array_of, u8, r, w, v, r.read_exact(&mut v[..]), w.write_all(&v[..]));
+array_impl!(le_i8_array_of, i8, r, w, v, r.read_i8_into(&mut v[..]), w.write_all(ne_bytes(&v[..])));
+array_impl!(ne_u8_array_of, u8, r, w, v, r.read_exact(&mut v[..]), w.write_all(&v[..]));
+array_impl!(ne_i8_array_of, i8, r, w, v, r.read_i8_into(&mut v[..]), w.write_all(ne_bytes(&v[..])));
+
+array_impl!(be_u16_array_of, u16, read_u16_into, write_u16_into, BigEndian);
+array_impl!(be_i16_array_of, i16, read_i16_into, write_i16_into, BigEndian);
+array_impl!(le_u16_array_of, u16, read_u16_into, write_u16_into, LittleEndian);
+array_impl!(le_i16_array_of, i16, read_i16_into, write_i16_into, LittleEndian);
+array_impl!(ne_u16_array_of, u16, read_u16_into, write_u16_into, NativeEndian);
+array_impl!(ne_i16_array_of, i16, read_i16_into, write_i16_into, NativeEndian);
+
+array_impl!(be_u32_array_of, u32, read_u32_into, write_u32_into, BigEndian);
+array_impl!(be_i32_array_of, i32, read_i32_into, write_i32_into, BigEndian);
+array_impl!(le_u32_array_of, u32, read_u32_into, write_u32_into, LittleEndian);
+array_impl!(le_i32_array_of, i32, read_i32_into, write_i32_into, LittleEndian);
+array_impl!(ne_u32_array_of, u32, read_u32_into, write_u32_into, NativeEndian);
+array_impl!(ne_i32_array_of, i32, read_i32_into, write_i32_into, NativeEndian);
+
+array_impl!(be_u64_array_of, u64, read_u64_into, write_u64_into, BigEndian);
+array_impl!(be_i64_array_of, i64, read_i64_into, write_i64_into, BigEndian);
+array_impl!(le_u64_array_of, u64, read_u64_into, write_u64_into, LittleEndian);
+array_impl!(le_i64_array_of, i64, read_i64_into, write_i64_into, LittleEndian);
+array_impl!(ne_u64_array_of, u64, read_u64_into, write_u64_into, NativeEndian);
+array_impl!(ne_i64_array_of, i64, read_i64_into, write_i64_into, NativeEndian);
+
+array_impl!(be_u128_array_of, u128, read_u128_into, write_u128_into, BigEndian);
+array_impl!(be_i128_array_of, i128, read_i128_into, write_i128_into, BigEndian);
+array_impl!(le_u128_array_of, u128, read_u128_into, write_u128_into, LittleEndian);
+array_impl!(le_i128_array_of, i128, read_i128_into, write_i128_into, LittleEndian);
+array_impl!(ne_u128_array_of, u128, read_u128_into, write_u128_into, NativeEndian);
+array_impl!(ne_i128_array_of, i128, read_i128_into, write_i128_into, NativeEndian);
+
+array_impl!(be_f32_array_of, f32, read_f32_into, write_f32_into, BigEndian);
+array_impl!(le_f32_array_of, f32, read_f32_into, write_f32_into, LittleEndian);
+array_impl!(ne_f32_array_of, f32, read_f32_into, write_f32_into, NativeEndian);
+
+array_impl!(be_f64_array_of, f64, read_f64_into, write_f64_into, BigEndian);
+array_impl!(le_f64_array_of, f64, read_f64_into, write_f64_into, LittleEndian);
+array_impl!(ne_f64_array_of, f64, read_f64_into, write_f64_into, NativeEndian);
+
+fn check_nbytes(nbytes: usize) {
+    if nbytes == 0 || nbytes > 8 {
+        panic!("Invalid number of bytes, must be between 1 and 8");
+    }
+}
+
+/// Reads/Writes a big endian unsigned integer of `nbytes` bytes.
+/// 
+/// ## Writing
+/// If the value doesn't fit in `nbytes` bytes 
+/// an error is returned.
+/// 
+/// # Panics
+/// If `nbytes` is not between 1 and 8 the function panics.
+/// 
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::numbers::be_uint;
+/// use bin_io::{ read, write };
+/// 
+/// let vec = Vec::new();
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// write(&mut cursor, &0x123456, be_uint(5))
+///     .unwrap();
+/// 
+/// assert_eq!(cursor.get_ref(), &[ 0x00, 0x00, 0x12, 0x34, 0x56 ]);
+/// 
+/// cursor.set_position(0);
+/// 
+/// let val = read(&mut cursor, be_uint(5))
+///     .unwrap();
+/// 
+/// assert_eq!(val, 0x123456);
+/// 
+/// // Doesn't fit!
+/// let err = write(&mut cursor, &0x10000, be_uint(2));
+/// 
+/// assert!(err.is_err());
+/// ```
+pub fn be_uint<R: Read + ?Sized, W: Write + ?Sized>(nbytes: usize) 
+-> (impl ReadFn<R, u64>, impl WriteFn<W, u64>) {
+
+    check_nbytes(nbytes);
+
+    (move |r: &mut R| {
+        r.read_uint::<BigEndian>(nbytes)
+    },
+    move |w: &mut W, v: &u64| {
+        write_uint::<BigEndian, _>(w, *v, nbytes)
+    })
+}
+
+/// Reads/Writes a little endian unsigned integer of `nbytes` bytes.
+/// 
+/// ## Writing
+/// If the value doesn't fit in `nbytes` bytes 
+/// an error is returned.
+/// 
+/// # Panics
+/// If `nbytes` is not between 1 and 8 the function panics.
+pub fn le_uint<R: Read + ?Sized, W: Write + ?Sized>(nbytes: usize) 
+-> (impl ReadFn<R, u64>, impl WriteFn<W, u64>) {
+
+    check_nbytes(nbytes);
+
+    (move |r: &mut R| {
+        r.read_uint::<LittleEndian>(nbytes)
+    },
+    move |w: &mut W, v: &u64| {
+        write_uint::<LittleEndian, _>(w, *v, nbytes)
+    })
+}
+
+/// Reads/Writes a big endian signed integer of `nbytes` bytes.
+/// 
+/// ## Reading
+/// The value is sign extended.
+/// 
+/// ## Writing
+/// If the value doesn't fit in `nbytes` bytes 
+/// an error is returned.
+/// 
+/// # Panics
+/// If `nbytes` is not between 1 and 8 the function panics.
+pub fn be_int<R: Read + ?Sized, W: Write + ?Sized>(nbytes: usize) 
+-> (impl ReadFn<R, i64>, impl WriteFn<W, i64>) {
+
+    check_nbytes(nbytes);
+
+    (move |r: &mut R| {
+        r.read_int::<BigEndian>(nbytes)
+    },
+    move |w: &mut W, v: &i64| {
+        write_int::<BigEndian, _>(w, *v, nbytes)
+    })
+}
+
+/// Reads/Writes a little endian signed integer of `nbytes` bytes.
+/// 
+/// ## Reading
+/// The value is sign extended.
+/// 
+/// ## Writing
+/// If the value doesn't fit in `nbytes` bytes 
+/// an error is returned.
+/// 
+/// # Panics
+/// If `nbytes` is not between 1 and 8 the function panics.
+/// 
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::numbers::{ le_int, le_i24 };
+/// use bin_io::read;
+/// 
+/// let vec = vec![ 0xfe, 0xff, 0xff, 0xfe, 0xff, 0xff ];
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// let a = read(&mut cursor, le_int(3))
+///     .unwrap();
+/// let b = read(&mut cursor, le_i24())
+///     .unwrap();
+/// 
+/// assert_eq!(a, -2);
+/// assert_eq!(b, -2);
+/// ```
+pub fn le_int<R: Read + ?Sized, W: Write + ?Sized>(nbytes: usize) 
+-> (impl ReadFn<R, i64>, impl WriteFn<W, i64>) {
+
+    check_nbytes(nbytes);
+
+    (move |r: &mut R| {
+        r.read_int::<LittleEndian>(nbytes)
+    },
+    move |w: &mut W, v: &i64| {
+        write_int::<LittleEndian, _>(w, *v, nbytes)
+    })
+}
+
+fn read_varint<R: Read + ?Sized>(r: &mut R, bits: u32) -> io::Result<u64> {
+    let mut v = 0;
+    let mut shift = 0;
+    loop {
+        let b = r.read_u8()?;
+        let low = (b & 0x7f) as u64;
+
+        if shift >= bits || low.checked_shr(bits - shift).unwrap_or(0) != 0 {
+            return Err(Error::from(BinError::CastFail));
+        }
+
+        v |= low << shift;
+        match b & 0x80 {
+            0 => return Ok(v),
+            _ => shift += 7
+        }
+    }
+}
+
+fn write_varint<W: Write + ?Sized>(w: &mut W, mut v: u64) -> io::Result<()> {
+    loop {
+        let b = (v & 0x7f) as u8;
+        v >>= 7;
+
+        match v {
+            0 => return w.write_u8(b),
+            _ => w.write_u8(b | 0x80)?
+        }
+    }
+}
+
+/// Reads/Writes an unsigned LEB128 variable length `u32`
+/// (also known as .NET's 7 bit encoded int).
+/// 
+/// ## Reading
+/// If the value doesn't fit in an `u32` an error is returned.
+/// 
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::numbers::varint_u32;
+/// use bin_io::{ read, write };
+/// 
+/// let vec = Vec::new();
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// write(&mut cursor, &300, varint_u32())
+///     .unwrap();
+/// 
+/// assert_eq!(cursor.get_ref(), &[ 0xac, 0x02 ]);
+/// 
+/// cursor.set_position(0);
+/// 
+/// let val = read(&mut cursor, varint_u32())
+///     .unwrap();
+/// 
+/// assert_eq!(val, 300);
+/// ```
+pub fn varint_u32<R: Read + ?Sized, W: Write + ?Sized>() 
+-> (impl ReadFn<R, u32>, impl WriteFn<W, u32>) {
+
+    (|r: &mut R| {
+        Ok(read_varint(r, 32)? as u32)
+    },
+    |w: &mut W, v: &u32| {
+        write_varint(w, *v as u64)
+    })
+}
+
+/// Reads/Writes an unsigned LEB128 variable length `u64`.
+/// 
+/// ## Reading
+/// If the value doesn't fit in an `u64` an error is returned.
+pub fn varint_u64<R: Read + ?Sized, W: Write + ?Sized>() 
+-> (impl ReadFn<R, u64>, impl WriteFn<W, u64>) {
+
+    (|r: &mut R| {
+        read_varint(r, 64)
+    },
+    |w: &mut W, v: &u64| {
+        write_varint(w, *v)
+    })
+}
+
+/// Rounding mode used when writing fixed point numbers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rounding {
+    /// Round to nearest, ties away from zero.
+    Nearest,
+    /// Round towards negative infinity.
+    Floor,
+    /// Round towards positive infinity.
+    Ceil,
+    /// Round towards zero.
+    Trunc
+}
+
+struct FixedFormat<I, const INT: u32, const FRAC: u32>(std::marker::PhantomData<I>);
+
+impl<I, const INT: u32, const FRAC: u32> FixedFormat<I, INT, FRAC> {
+    const BITS: u32 = {
+        let bits = INT + FRAC;
+        assert!(bits > 0 && bits <= 64 && bits as usize <= std::mem::size_of::<I>() * 8, 
+            "Invalid fixed point format");
+        bits
+    };
+}
+
+/// Reads/Writes a fixed point number, with `INT` integer bits
+/// and `FRAC` fractional bits, stored in the integer read/written
+/// by the passed parser.
+/// 
+/// For signed integers the sign bit is counted in `INT`,
+/// so for example OpenType's `F2Dot14` is `fixed_point::<2, 14, _, _, _>`.
+/// 
+/// ## Reading
+/// The function reads the integer, keeps its low `INT + FRAC` 
+/// bits (sign extended for signed integers) and scales it.
+/// 
+/// ## Writing
+/// The function scales the value, rounds it to the nearest
+/// integer and writes it, if the result doesn't fit in 
+/// `INT + FRAC` bits (or the value is NaN) an error is returned.
+/// Use `fixed_point_with` to pick a different rounding mode.
+/// 
+/// # Remarks
+/// The conversion is exact as long as `INT + FRAC` 
+/// is at most 53 bits. If `INT + FRAC` is 0 or bigger 
+/// than the integer type the code doesn't compile.
+/// 
+/// The other type parameters (reader, writer and integer 
+/// type) are inferred, so they're written as `_`.
+/// 
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::numbers::{ be_i32, be_i16, fixed_point };
+/// use bin_io::{ read, write };
+/// 
+/// let vec = Vec::new();
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// // OpenType's Fixed (16.16)
+/// write(&mut cursor, &1.5, fixed_point::<16, 16, _, _, _>(be_i32()))
+///     .unwrap();
+/// 
+/// assert_eq!(cursor.get_ref(), &[ 0x00, 0x01, 0x80, 0x00 ]);
+/// 
+/// cursor.set_position(0);
+/// 
+/// let val = read(&mut cursor, fixed_point::<16, 16, _, _, _>(be_i32()))
+///     .unwrap();
+/// 
+/// assert_eq!(val, 1.5);
+/// 
+/// // OpenType's F2Dot14 can't go beyond 2
+/// let err = write(&mut cursor, &2.0, fixed_point::<2, 14, _, _, _>(be_i16()));
+/// 
+/// assert!(err.is_err());
+/// 
+/// // A 4.4 number in the low byte of an i16
+/// let vec = vec![ 0x12, 0xf8 ];
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// let val = read(&mut cursor, fixed_point::<4, 4, _, _, _>(be_i16()))
+///     .unwrap();
+/// 
+/// assert_eq!(val, -0.5);
+/// ```
+/// The format is checked at compile time.
+/// ```compile_fail
+/// use bin_io::numbers::{ be_i16, fixed_point };
+/// use bin_io::read;
+/// 
+/// let mut cursor = std::io::Cursor::new(vec![ 0x00, 0x00 ]);
+/// 
+/// // 16.16 doesn't fit in an i16
+/// let val = read(&mut cursor, fixed_point::<16, 16, _, _, _>(be_i16()));
+/// ```
+pub fn fixed_point<const INT: u32, const FRAC: u32, R, W, I>(f: (impl ReadFn<R, I>, impl WriteFn<W, I>))
+-> (impl ReadFn<R, f64>, impl WriteFn<W, f64>)
+where R: Read + ?Sized, W: Write + ?Sized, I: TryFrom<i128> + Into<i128> {
+
+    fixed_point_with::<INT, FRAC, R, W, I>(f, Rounding::Nearest)
+}
+
+/// Reads/Writes a fixed point number like `fixed_point`, 
+/// rounding the values written with the passed mode.
+/// 
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::numbers::{ be_u8, fixed_point_with, Rounding };
+/// use bin_io::write;
+/// 
+/// let vec = Vec::new();
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// // A 4.4 number, 1.99 is 31.84 sixteenths
+/// write(&mut cursor, &1.99, fixed_point_with::<4, 4, _, _, _>(be_u8(), Rounding::Floor))
+///     .unwrap();
+/// 
+/// assert_eq!(cursor.get_ref(), &[ 0x1f ]);
+/// ```
+pub fn fixed_point_with<const INT: u32, const FRAC: u32, R, W, I>(f: (impl ReadFn<R, I>, impl WriteFn<W, I>), rounding: Rounding)
+-> (impl ReadFn<R, f64>, impl WriteFn<W, f64>)
+where R: Read + ?Sized, W: Write + ?Sized, I: TryFrom<i128> + Into<i128> {
+
+    let bits = FixedFormat::<I, INT, FRAC>::BITS;
+
+    let (rf, wf) = f;
+    let scale = (FRAC as f64).exp2();
+    let signed = I::try_from(-1).is_ok();
+    let (min, max) = match signed {
+        true => (-(1i128 << (bits - 1)), (1i128 << (bits - 1)) - 1),
+        false => (0, (1i128 << bits) - 1)
+    };
+
+    (move |r: &mut R| {
+        let v: i128 = rf(r)?.into();
+        let v = match signed {
+            true => (v << (128 - bits)) >> (128 - bits),
+            false => v & max
+        };
+
+        Ok(v as f64 / scale)
+    },
+    move |w: &mut W, v: &f64| {
+        let v = v * scale;
+        let v = match rounding {
+            Rounding::Nearest => v.round(),
+            Rounding::Floor => v.floor(),
+            Rounding::Ceil => v.ceil(),
+            Rounding::Trunc => v.trunc()
+        };
+
+        match v >= min as f64 && v <= max as f64 {
+            true => wf(w, &I::try_from(v as i128)
+                .map_err(|_| Error::from(BinError::OutOfRange))?),
+            false => Err(Error::from(BinError::OutOfRange))
+        }
+    })
+}
+
+/// Reads/Writes a packed BCD (binary coded decimal) unsigned 
+/// integer of `nbytes` bytes, two digits per byte with the 
+/// most significant digit first.
+/// 
+/// ## Reading
+/// If a nibble is not a decimal digit, or the value doesn't 
+/// fit in an `u64`, an error is returned.
+/// 
+/// ## Writing
+/// If the value doesn't fit in `nbytes * 2` digits 
+/// an error is returned.
+/// 
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::numbers::bcd;
+/// use bin_io::{ read, write };
+/// 
+/// let vec = Vec::new();
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// write(&mut cursor, &1234, bcd(3))
+///     .unwrap();
+/// 
+/// assert_eq!(cursor.get_ref(), &[ 0x00, 0x12, 0x34 ]);
+/// 
+/// cursor.set_position(0);
+/// 
+/// let val = read(&mut cursor, bcd(3))
+///     .unwrap();
+/// 
+/// assert_eq!(val, 1234);
+/// ```
+pub fn bcd<R: Read + ?Sized, W: Write + ?Sized>(nbytes: usize) 
+-> (impl ReadFn<R, u64>, impl WriteFn<W, u64>) {
+
+    (move |r: &mut R| {
+        let mut s = vec![0; nbytes];
+        r.read_exact(&mut s[..])?;
+
+        s.iter()
+            .flat_map(|b| vec![ b >> 4, b & 0xf ])
+            .try_fold(0u64, |v, d| match d {
+                0..=9 => v.checked_mul(10)
+                    .and_then(|v| v.checked_add(d as u64))
+                    .ok_or_else(|| Error::from(BinError::OutOfRange)),
+                _ => Err(Error::from(BinError::CheckFail))
+            })
+    },
+    move |w: &mut W, v: &u64| {
+        let mut s = vec![0; nbytes];
+        let mut v = *v;
+
+        for b in s.iter_mut().rev() {
+            *b = (v % 10) as u8 | (((v / 10) % 10) as u8) << 4;
+            v /= 100;
+        }
+
+        match v {
+            0 => w.write_all(&s[..]),
+            _ => Err(Error::from(BinError::OutOfRange))
+        }
+    })
+}
+
+/// Layout of an ascii number field.
+/// 
+/// When reading, leading and trailing spaces and 
+/// null bytes are always ignored, along with the fill 
+/// byte and the terminator unless they're digits.
+/// 
+/// # Remarks
+/// Digits used as fill are only stripped by the number 
+/// parsing itself, so zeros on the left are fine but 
+/// zeros on the right (`"644000"`) can't be read back.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Pad {
+    /// Byte used to fill the field.
+    pub fill: u8,
+    /// Whether the digits are aligned to the right 
+    /// (so the field is filled on the left).
+    pub right: bool,
+    /// Optional terminator, written as the last 
+    /// byte of the field.
+    pub term: Option<u8>
+}
+
+impl Pad {
+    /// Digits aligned to the right, filled with zeros (`"000644"`).
+    pub fn zeros() -> Self {
+        Self { fill: b'0', right: true, term: None }
+    }
+
+    /// Digits aligned to the left, filled with spaces (`"644   "`).
+    pub fn spaces() -> Self {
+        Self { fill: b' ', right: false, term: None }
+    }
+
+    /// Adds a terminator to the field.
+    pub fn with_term(self, term: u8) -> Self {
+        Self { term: Some(term), ..self }
+    }
+}
+
+fn ascii_number<R: Read + ?Sized, W: Write + ?Sized>(width: usize, radix: u32, pad: Pad) 
+-> (impl ReadFn<R, u64>, impl WriteFn<W, u64>) {
+
+    (move |r: &mut R| {
+        let mut s = vec![0; width];
+        r.read_exact(&mut s[..])?;
+
+        let digit = |b: u8| (b as char).is_digit(radix);
+        let blank = |b: &u8| *b == b' ' || *b == 0 
+            || (*b == pad.fill && !digit(pad.fill))
+            || (Some(*b) == pad.term && !digit(*b));
+        let start = s.iter().position(|b| !blank(b)).unwrap_or(width);
+        let end = s.iter().rposition(|b| !blank(b)).map_or(start, |e| e + 1);
+        let s = &s[start..end];
+
+        match s.iter().all(|b| digit(*b)) {
+            true if s.is_empty() => Ok(0),
+            true => u64::from_str_radix(std::str::from_utf8(s).unwrap(), radix)
+                .map_err(|_| Error::from(BinError::OutOfRange)),
+            false => Err(Error::from(BinError::CheckFail))
+        }
+    },
+    move |w: &mut W, v: &u64| {
+        let digits = match radix {
+            8 => format!("{:o}", v),
+            16 => format!("{:x}", v),
+            _ => format!("{}", v)
+        };
+
+        let len = width.checked_sub(pad.term.map_or(0, |_| 1))
+            .filter(|len| digits.len() <= *len)
+            .ok_or_else(|| Error::from(BinError::OutOfRange))?;
+
+        let fill = vec![pad.fill; len - digits.len()];
+        let mut s = match pad.right {
+            true => [ &fill[..], digits.as_bytes() ].concat(),
+            false => [ digits.as_bytes(), &fill[..] ].concat()
+        };
+        s.extend(pad.term);
+
+        w.write_all(&s[..])
+    })
+}
+
+/// Reads/Writes an unsigned integer as ascii 
+/// decimal digits in a field of `width` bytes.
+/// 
+/// ## Reading
+/// Leading and trailing spaces and null bytes are ignored, 
+/// a blank field is read as 0. If the field contains anything
+/// else an error is returned.
+/// 
+/// ## Writing
+/// The digits are written according to `pad`. If the digits
+/// don't fit in the field an error is returned.
+/// 
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::numbers::{ ascii_decimal, Pad };
+/// use bin_io::{ read, write };
+/// 
+/// let vec = Vec::new();
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// // An `ar` header file size
+/// write(&mut cursor, &1234, ascii_decimal(10, Pad::spaces()))
+///     .unwrap();
+/// 
+/// assert_eq!(cursor.get_ref(), b"1234      ");
+/// 
+/// cursor.set_position(0);
+/// 
+/// let val = read(&mut cursor, ascii_decimal(10, Pad::spaces()))
+///     .unwrap();
+/// 
+/// assert_eq!(val, 1234);
+/// ```
+pub fn ascii_decimal<R: Read + ?Sized, W: Write + ?Sized>(width: usize, pad: Pad) 
+-> (impl ReadFn<R, u64>, impl WriteFn<W, u64>) {
+
+    ascii_number(width, 10, pad)
+}
+
+/// Reads/Writes an unsigned integer as ascii 
+/// octal digits in a field of `width` bytes.
+/// 
+/// ## Reading
+/// Leading and trailing spaces and null bytes are ignored, 
+/// a blank field is read as 0. If the field contains anything
+/// else an error is returned.
+/// 
+/// ## Writing
+/// The digits are written according to `pad`. If the digits
+/// don't fit in the field an error is returned.
+/// 
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::numbers::{ ascii_octal, Pad };
+/// use bin_io::write;
+/// 
+/// let vec = Vec::new();
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// // A tar header file mode
+/// write(&mut cursor, &0o644, ascii_octal(8, Pad::zeros().with_term(0)))
+///     .unwrap();
+/// 
+/// assert_eq!(cursor.get_ref(), b"0000644\0");
+/// 
+/// // No room for the terminator
+/// let err = write(&mut cursor, &0, ascii_octal(0, Pad::zeros().with_term(0)));
+/// 
+/// assert!(err.is_err());
+/// ```
+pub fn ascii_octal<R: Read + ?Sized, W: Write + ?Sized>(width: usize, pad: Pad) 
+-> (impl ReadFn<R, u64>, impl WriteFn<W, u64>) {
+
+    ascii_number(width, 8, pad)
+}
+
+/// Reads/Writes an unsigned integer as ascii 
+/// hexadecimal digits in a field of `width` bytes.
+/// 
+/// ## Reading
+/// Leading and trailing spaces and null bytes are ignored, 
+/// a blank field is read as 0. Both upper and lower case
+/// digits are accepted. If the field contains anything
+/// else an error is returned.
+/// 
+/// ## Writing
+/// The digits are written (lower case) according to `pad`.
+/// If the digits don't fit in the field an error is returned.
+/// 
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::numbers::{ ascii_hex, Pad };
+/// use bin_io::read;
+/// 
+/// let vec = b"  BEEF\0\0".to_vec();
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// let val = read(&mut cursor, ascii_hex(8, Pad::zeros()))
+///     .unwrap();
+/// 
+/// assert_eq!(val, 0xbeef);
+/// 
+/// // Custom fill bytes are stripped too
+/// let pad = Pad { fill: b'*', right: true, term: Some(b'\n') };
+/// let vec = b"**ff\n".to_vec();
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// let val = read(&mut cursor, ascii_hex(5, pad))
+///     .unwrap();
+/// 
+/// assert_eq!(val, 0xff);
+/// ```
+pub fn ascii_hex<R: Read + ?Sized, W: Write + ?Sized>(width: usize, pad: Pad) 
+-> (impl ReadFn<R, u64>, impl WriteFn<W, u64>) {
+
+    ascii_number(width, 16, pad)
+}
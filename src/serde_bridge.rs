@@ -0,0 +1,914 @@
+//! Contains the `serde` bridge.
+//!
+//! This module is only available with the `serde` feature,
+//! and allows any type implementing `Serialize` and
+//! `Deserialize` to be read/written without writing a
+//! `seq!` for it. The encoding is built on top of the
+//! parsers in `numbers` and `strings`, and can be tuned
+//! with a `Config`.
+//!
+//! # Encoding
+//! * Integers, floats and `char` (as an `u32`) use the
+//!   configured endianness.
+//! * `bool` is a single byte, `0x00` or `0x01`.
+//! * Strings, byte arrays, sequences and maps are prefixed
+//!   by their length, whose type is configurable.
+//! * `Option` is a single byte tag (`0x00` for `None`,
+//!   `0x01` for `Some`) followed by the value.
+//! * Enums are prefixed by their variant index, whose type
+//!   is configurable.
+//! * Structs and tuples are just their fields in order.
+//!
+//! # Examples
+//! ```
+//! use std::io::Cursor;
+//! use serde::{ Serialize, Deserialize };
+//! use bin_io::{ seq, read, write };
+//! use bin_io::numbers::be_u8;
+//! use bin_io::serde_bridge::serde_parser;
+//!
+//! #[derive(Serialize, Deserialize, Debug, PartialEq)]
+//! struct Config {
+//!     name: String,
+//!     size: u16,
+//!     flags: Option<u8>
+//! }
+//!
+//! #[derive(Debug, PartialEq)]
+//! struct Header {
+//!     version: u8,
+//!     config: Config
+//! }
+//!
+//! let header = Header {
+//!     version: 1,
+//!     config: Config { name: "foo".to_owned(), size: 0x20, flags: None }
+//! };
+//!
+//! let vec = Vec::new();
+//! let mut cursor = Cursor::new(vec);
+//!
+//! write(&mut cursor, &header, seq!(
+//!     Header { version, config },
+//!     version: be_u8() =>
+//!     config: serde_parser() =>
+//! )).unwrap();
+//!
+//! assert_eq!(cursor.get_ref(), &[
+//!     0x01,
+//!     0x03, 0x00, 0x00, 0x00, 0x66, 0x6f, 0x6f,
+//!     0x20, 0x00,
+//!     0x00
+//! ]);
+//!
+//! cursor.set_position(0);
+//!
+//! let other = read(&mut cursor, seq!(
+//!     Header { version, config },
+//!     version: be_u8() =>
+//!     config: serde_parser() =>
+//! )).unwrap();
+//!
+//! assert_eq!(other, header);
+//! ```
+
+use crate::{ ReadFn, WriteFn, BinError, read, write, try_cast, read_len_bytes };
+use crate::numbers::*;
+use std::io::{ self, Read, Write };
+use std::convert::TryFrom;
+use std::fmt::{ self, Display };
+use std::marker::PhantomData;
+
+use serde::{ ser, de, Serialize };
+use serde::de::{ DeserializeOwned, DeserializeSeed, IntoDeserializer, Visitor };
+
+/// Integer type used for length prefixes and variant indices.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Prefix {
+    U8,
+    U16,
+    U32,
+    U64
+}
+
+/// Encoding used for strings.
+///
+/// The length prefix counts bytes for `Utf8`, and
+/// code units for `Utf16`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Encoding {
+    Utf8,
+    Utf16
+}
+
+/// Configuration of the encoding.
+///
+/// The default configuration uses little endian numbers,
+/// `u32` length prefixes and variant indices, and utf8 strings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Config {
+    pub endian: Endian,
+    pub len: Prefix,
+    pub variant: Prefix,
+    pub encoding: Encoding
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            endian: Endian::Little,
+            len: Prefix::U32,
+            variant: Prefix::U32,
+            encoding: Encoding::Utf8
+        }
+    }
+}
+
+/// Error type used by `Serializer` and `Deserializer`.
+///
+/// # Remarks
+/// This is just a wrapper around `std::io::Error`, needed
+/// by `serde`, and it's converted back when using
+/// `to_writer`, `from_reader` or `serde_parser`.
+#[derive(Debug)]
+pub struct Error(io::Error);
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+impl ser::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self(io::Error::from(BinError::Serde(msg.to_string())))
+    }
+}
+
+impl de::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self(io::Error::from(BinError::Serde(msg.to_string())))
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Self(err)
+    }
+}
+
+impl From<BinError> for Error {
+    fn from(err: BinError) -> Self {
+        Self(io::Error::from(err))
+    }
+}
+
+impl From<Error> for io::Error {
+    fn from(err: Error) -> Self {
+        err.0
+    }
+}
+
+type Result<T> = std::result::Result<T, Error>;
+
+macro_rules! write_endian {
+    ($s:ident, $v:expr, $be:expr, $le:expr) => {
+        match $s.config.endian {
+            Endian::Big => write($s.w, $v, $be),
+            Endian::Little => write($s.w, $v, $le)
+        }
+    };
+}
+
+macro_rules! read_endian {
+    ($s:ident, $be:expr, $le:expr) => {
+        match $s.config.endian {
+            Endian::Big => read($s.r, $be),
+            Endian::Little => read($s.r, $le)
+        }
+    };
+}
+
+/// Writes a value using the `serde` bridge.
+///
+/// # Examples
+/// ```
+/// use bin_io::numbers::Endian;
+/// use bin_io::serde_bridge::{ to_writer, Config };
+///
+/// let mut vec = Vec::new();
+/// let config = Config { endian: Endian::Big, ..Config::default() };
+///
+/// to_writer(&mut vec, &(0x10u8, 0x20u16), config)
+///     .unwrap();
+///
+/// assert_eq!(vec, &[ 0x10, 0x00, 0x20 ]);
+/// ```
+pub fn to_writer<W: Write + ?Sized, T: Serialize + ?Sized>(w: &mut W, v: &T, config: Config)
+-> io::Result<()> {
+
+    v.serialize(&mut Serializer::new(w, config))
+        .map_err(io::Error::from)
+}
+
+/// Reads a value using the `serde` bridge.
+///
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::serde_bridge::{ from_reader, Config, Prefix };
+///
+/// let vec = vec![ 0x02, 0x61, 0x62 ];
+/// let mut cursor = Cursor::new(vec);
+/// let config = Config { len: Prefix::U8, ..Config::default() };
+///
+/// let string: String = from_reader(&mut cursor, config)
+///     .unwrap();
+///
+/// assert_eq!(string, "ab");
+/// ```
+pub fn from_reader<R: Read + ?Sized, T: DeserializeOwned>(r: &mut R, config: Config)
+-> io::Result<T> {
+
+    T::deserialize(&mut Deserializer::new(r, config))
+        .map_err(io::Error::from)
+}
+
+/// Reads/Writes a value using the `serde` bridge
+/// with the default `Config`.
+///
+/// # Remarks
+/// The type is usually inferred, otherwise use
+/// `Serde::<T>::parser()`.
+pub fn serde_parser<T, R: Read + ?Sized, W: Write + ?Sized>()
+-> (impl ReadFn<R, T>, impl WriteFn<W, T>)
+where T: Serialize + DeserializeOwned {
+
+    serde_parser_with(Config::default())
+}
+
+/// Reads/Writes a value using the `serde` bridge
+/// with the given `Config`.
+///
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::{ read, write };
+/// use bin_io::serde_bridge::{ serde_parser_with, Config, Encoding, Prefix };
+///
+/// let vec = Vec::new();
+/// let mut cursor = Cursor::new(vec);
+///
+/// let config = Config {
+///     len: Prefix::U8,
+///     encoding: Encoding::Utf16,
+///     ..Config::default()
+/// };
+///
+/// let value = vec![ "a".to_owned() ];
+///
+/// write(&mut cursor, &value, serde_parser_with(config))
+///     .unwrap();
+///
+/// assert_eq!(cursor.get_ref(), &[ 0x01, 0x01, 0x61, 0x00 ]);
+///
+/// cursor.set_position(0);
+///
+/// let other: Vec<String> = read(&mut cursor, serde_parser_with(config))
+///     .unwrap();
+///
+/// assert_eq!(other, value);
+/// ```
+pub fn serde_parser_with<T, R: Read + ?Sized, W: Write + ?Sized>(config: Config)
+-> (impl ReadFn<R, T>, impl WriteFn<W, T>)
+where T: Serialize + DeserializeOwned {
+
+    (move |r: &mut R| {
+
+        from_reader(r, config)
+    },
+    move |w: &mut W, v: &T| {
+
+        to_writer(w, v, config)
+    })
+}
+
+/// Names the type read/written by the `serde` bridge.
+/// 
+/// A turbofish must list every generic argument of a function,
+/// so `serde_parser` can't take the value type alone without 
+/// also naming the reader and writer. `Serde::<T>` only names
+/// the value type, and leaves the rest to inference.
+/// 
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use serde::{ Serialize, Deserialize };
+/// use bin_io::{ read, write };
+/// use bin_io::serde_bridge::Serde;
+/// 
+/// #[derive(Serialize, Deserialize)]
+/// struct Point {
+///     x: i16,
+///     y: i16
+/// }
+/// 
+/// let vec = Vec::new();
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// write(&mut cursor, &Point { x: 1, y: -1 }, Serde::<Point>::parser())
+///     .unwrap();
+/// 
+/// assert_eq!(cursor.get_ref(), &[ 0x01, 0x00, 0xff, 0xff ]);
+/// 
+/// cursor.set_position(0);
+/// 
+/// let point = read(&mut cursor, Serde::<Point>::parser())
+///     .unwrap();
+/// 
+/// assert_eq!((point.x, point.y), (1, -1));
+/// ```
+pub struct Serde<T>(PhantomData<T>);
+
+impl<T: Serialize + DeserializeOwned> Serde<T> {
+    /// Reads/Writes a `T` with the default `Config`,
+    /// see `serde_parser`.
+    pub fn parser<R: Read + ?Sized, W: Write + ?Sized>()
+    -> (impl ReadFn<R, T>, impl WriteFn<W, T>) {
+
+        serde_parser_with(Config::default())
+    }
+
+    /// Reads/Writes a `T` with the given `Config`,
+    /// see `serde_parser_with`.
+    pub fn parser_with<R: Read + ?Sized, W: Write + ?Sized>(config: Config)
+    -> (impl ReadFn<R, T>, impl WriteFn<W, T>) {
+
+        serde_parser_with(config)
+    }
+}
+
+/// `serde` serializer writing to a stream.
+pub struct Serializer<'a, W: Write + ?Sized> {
+    w: &'a mut W,
+    config: Config
+}
+
+impl<'a, W: Write + ?Sized> Serializer<'a, W> {
+    pub fn new(w: &'a mut W, config: Config) -> Self {
+        Self { w, config }
+    }
+
+    fn write_prefix(&mut self, prefix: Prefix, v: usize) -> Result<()> {
+        Ok(match prefix {
+            Prefix::U8 => write_endian!(self, &v, try_cast(be_u8()), try_cast(le_u8())),
+            Prefix::U16 => write_endian!(self, &v, try_cast(be_u16()), try_cast(le_u16())),
+            Prefix::U32 => write_endian!(self, &v, try_cast(be_u32()), try_cast(le_u32())),
+            Prefix::U64 => write_endian!(self, &v, try_cast(be_u64()), try_cast(le_u64()))
+        }?)
+    }
+}
+
+impl<'a, 'b, W: Write + ?Sized> ser::Serializer for &'b mut Serializer<'a, W> {
+    type Ok = ();
+    type Error = Error;
+
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    fn serialize_bool(self, v: bool) -> Result<()> {
+        Ok(write(self.w, &(v as u8), be_u8())?)
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<()> {
+        Ok(write(self.w, &v, be_i8())?)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<()> {
+        Ok(write_endian!(self, &v, be_i16(), le_i16())?)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<()> {
+        Ok(write_endian!(self, &v, be_i32(), le_i32())?)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<()> {
+        Ok(write_endian!(self, &v, be_i64(), le_i64())?)
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<()> {
+        Ok(write_endian!(self, &v, be_i128(), le_i128())?)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<()> {
+        Ok(write(self.w, &v, be_u8())?)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<()> {
+        Ok(write_endian!(self, &v, be_u16(), le_u16())?)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<()> {
+        Ok(write_endian!(self, &v, be_u32(), le_u32())?)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<()> {
+        Ok(write_endian!(self, &v, be_u64(), le_u64())?)
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<()> {
+        Ok(write_endian!(self, &v, be_u128(), le_u128())?)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<()> {
+        Ok(write_endian!(self, &v, be_f32(), le_f32())?)
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<()> {
+        Ok(write_endian!(self, &v, be_f64(), le_f64())?)
+    }
+
+    fn serialize_char(self, v: char) -> Result<()> {
+        self.serialize_u32(v as u32)
+    }
+
+    fn serialize_str(self, v: &str) -> Result<()> {
+        match self.config.encoding {
+            Encoding::Utf8 => {
+                self.write_prefix(self.config.len, v.len())?;
+                Ok(self.w.write_all(v.as_bytes())?)
+            },
+            Encoding::Utf16 => {
+                self.write_prefix(self.config.len, v.encode_utf16().count())?;
+                for c in v.encode_utf16() {
+                    write_endian!(self, &c, be_u16(), le_u16())?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<()> {
+        self.write_prefix(self.config.len, v.len())?;
+        Ok(self.w.write_all(v)?)
+    }
+
+    fn serialize_none(self) -> Result<()> {
+        self.serialize_u8(0)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, v: &T) -> Result<()> {
+        self.serialize_u8(1)?;
+        v.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<()> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<()> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(self, _name: &'static str, idx: u32, _variant: &'static str)
+    -> Result<()> {
+        self.write_prefix(self.config.variant, idx as usize)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(self, _name: &'static str, v: &T)
+    -> Result<()> {
+        v.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(self, _name: &'static str, idx: u32,
+        _variant: &'static str, v: &T) -> Result<()> {
+        self.write_prefix(self.config.variant, idx as usize)?;
+        v.serialize(self)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self> {
+        let len = len.ok_or_else(|| <Error as ser::Error>::custom("Sequence length is required"))?;
+        self.write_prefix(self.config.len, len)?;
+        Ok(self)
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Self> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_variant(self, _name: &'static str, idx: u32, _variant: &'static str,
+        _len: usize) -> Result<Self> {
+        self.write_prefix(self.config.variant, idx as usize)?;
+        Ok(self)
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self> {
+        let len = len.ok_or_else(|| <Error as ser::Error>::custom("Map length is required"))?;
+        self.write_prefix(self.config.len, len)?;
+        Ok(self)
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self> {
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(self, _name: &'static str, idx: u32, _variant: &'static str,
+        _len: usize) -> Result<Self> {
+        self.write_prefix(self.config.variant, idx as usize)?;
+        Ok(self)
+    }
+}
+
+impl<'a, 'b, W: Write + ?Sized> ser::SerializeSeq for &'b mut Serializer<'a, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, v: &T) -> Result<()> {
+        v.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a, 'b, W: Write + ?Sized> ser::SerializeTuple for &'b mut Serializer<'a, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, v: &T) -> Result<()> {
+        v.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a, 'b, W: Write + ?Sized> ser::SerializeTupleStruct for &'b mut Serializer<'a, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, v: &T) -> Result<()> {
+        v.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a, 'b, W: Write + ?Sized> ser::SerializeTupleVariant for &'b mut Serializer<'a, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, v: &T) -> Result<()> {
+        v.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a, 'b, W: Write + ?Sized> ser::SerializeMap for &'b mut Serializer<'a, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, k: &T) -> Result<()> {
+        k.serialize(&mut **self)
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, v: &T) -> Result<()> {
+        v.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a, 'b, W: Write + ?Sized> ser::SerializeStruct for &'b mut Serializer<'a, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, _key: &'static str, v: &T)
+    -> Result<()> {
+        v.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a, 'b, W: Write + ?Sized> ser::SerializeStructVariant for &'b mut Serializer<'a, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, _key: &'static str, v: &T)
+    -> Result<()> {
+        v.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// `serde` deserializer reading from a stream.
+///
+/// # Remarks
+/// Since the encoding is not self describing
+/// `deserialize_any` is not supported.
+pub struct Deserializer<'a, R: Read + ?Sized> {
+    r: &'a mut R,
+    config: Config
+}
+
+impl<'a, R: Read + ?Sized> Deserializer<'a, R> {
+    pub fn new(r: &'a mut R, config: Config) -> Self {
+        Self { r, config }
+    }
+
+    fn read_prefix(&mut self, prefix: Prefix) -> Result<usize> {
+        Ok(match prefix {
+            Prefix::U8 => read_endian!(self, try_cast(be_u8()), try_cast(le_u8())),
+            Prefix::U16 => read_endian!(self, try_cast(be_u16()), try_cast(le_u16())),
+            Prefix::U32 => read_endian!(self, try_cast(be_u32()), try_cast(le_u32())),
+            Prefix::U64 => read_endian!(self, try_cast(be_u64()), try_cast(le_u64()))
+        }?)
+    }
+
+    fn read_string(&mut self) -> Result<String> {
+        let len = self.read_prefix(self.config.len)?;
+
+        match self.config.encoding {
+            Encoding::Utf8 => Ok(String::from_utf8(read_len_bytes(self.r, len)?)
+                .map_err(BinError::from)?),
+            Encoding::Utf16 => {
+                let mut s = Vec::new();
+                for _ in 0..len {
+                    s.push(read_endian!(self, be_u16(), le_u16())?);
+                }
+
+                Ok(String::from_utf16(&s[..])
+                    .map_err(BinError::from)?)
+            }
+        }
+    }
+}
+
+impl<'de, 'a, 'b, R: Read + ?Sized> de::Deserializer<'de> for &'b mut Deserializer<'a, R> {
+    type Error = Error;
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value> {
+        Err(<Error as de::Error>::custom("Encoding is not self describing"))
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match read(self.r, be_u8())? {
+            0 => visitor.visit_bool(false),
+            1 => visitor.visit_bool(true),
+            _ => Err(Error::from(BinError::CheckFail))
+        }
+    }
+
+    fn deserialize_i8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_i8(read(self.r, be_i8())?)
+    }
+
+    fn deserialize_i16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_i16(read_endian!(self, be_i16(), le_i16())?)
+    }
+
+    fn deserialize_i32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_i32(read_endian!(self, be_i32(), le_i32())?)
+    }
+
+    fn deserialize_i64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_i64(read_endian!(self, be_i64(), le_i64())?)
+    }
+
+    fn deserialize_i128<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_i128(read_endian!(self, be_i128(), le_i128())?)
+    }
+
+    fn deserialize_u8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_u8(read(self.r, be_u8())?)
+    }
+
+    fn deserialize_u16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_u16(read_endian!(self, be_u16(), le_u16())?)
+    }
+
+    fn deserialize_u32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_u32(read_endian!(self, be_u32(), le_u32())?)
+    }
+
+    fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_u64(read_endian!(self, be_u64(), le_u64())?)
+    }
+
+    fn deserialize_u128<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_u128(read_endian!(self, be_u128(), le_u128())?)
+    }
+
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_f32(read_endian!(self, be_f32(), le_f32())?)
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_f64(read_endian!(self, be_f64(), le_f64())?)
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        let c: u32 = read_endian!(self, be_u32(), le_u32())?;
+
+        visitor.visit_char(std::char::from_u32(c)
+            .ok_or(BinError::CastFail)?)
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_string(self.read_string()?)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_string(self.read_string()?)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_byte_buf(visitor)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        let len = self.read_prefix(self.config.len)?;
+
+        visitor.visit_byte_buf(read_len_bytes(self.r, len)?)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match read(self.r, be_u8())? {
+            0 => visitor.visit_none(),
+            1 => visitor.visit_some(self),
+            _ => Err(Error::from(BinError::CheckFail))
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V)
+    -> Result<V::Value> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V)
+    -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        let len = self.read_prefix(self.config.len)?;
+        visitor.visit_seq(Access { de: self, len })
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value> {
+        visitor.visit_seq(Access { de: self, len })
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(self, _name: &'static str, len: usize,
+        visitor: V) -> Result<V::Value> {
+        visitor.visit_seq(Access { de: self, len })
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        let len = self.read_prefix(self.config.len)?;
+        visitor.visit_map(Access { de: self, len })
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(self, _name: &'static str,
+        fields: &'static [&'static str], visitor: V) -> Result<V::Value> {
+        visitor.visit_seq(Access { de: self, len: fields.len() })
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(self, _name: &'static str,
+        _variants: &'static [&'static str], visitor: V) -> Result<V::Value> {
+        visitor.visit_enum(self)
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value> {
+        Err(<Error as de::Error>::custom("Identifiers are not supported"))
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value> {
+        Err(<Error as de::Error>::custom("Encoding is not self describing"))
+    }
+}
+
+struct Access<'a, 'b, R: Read + ?Sized> {
+    de: &'b mut Deserializer<'a, R>,
+    len: usize
+}
+
+impl<'de, 'a, 'b, R: Read + ?Sized> de::SeqAccess<'de> for Access<'a, 'b, R> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T)
+    -> Result<Option<T::Value>> {
+        match self.len {
+            0 => Ok(None),
+            _ => {
+                self.len -= 1;
+                Ok(Some(seed.deserialize(&mut *self.de)?))
+            }
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.len)
+    }
+}
+
+impl<'de, 'a, 'b, R: Read + ?Sized> de::MapAccess<'de> for Access<'a, 'b, R> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K)
+    -> Result<Option<K::Value>> {
+        match self.len {
+            0 => Ok(None),
+            _ => {
+                self.len -= 1;
+                Ok(Some(seed.deserialize(&mut *self.de)?))
+            }
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
+        seed.deserialize(&mut *self.de)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.len)
+    }
+}
+
+impl<'de, 'a, 'b, R: Read + ?Sized> de::EnumAccess<'de> for &'b mut Deserializer<'a, R> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self)> {
+        let idx = self.read_prefix(self.config.variant)?;
+        let idx = u32::try_from(idx)
+            .map_err(|_| BinError::CastFail)?;
+
+        let value = seed.deserialize(IntoDeserializer::<Error>::into_deserializer(idx))?;
+        Ok((value, self))
+    }
+}
+
+impl<'de, 'a, 'b, R: Read + ?Sized> de::VariantAccess<'de> for &'b mut Deserializer<'a, R> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value> {
+        de::Deserializer::deserialize_tuple(self, len, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(self, fields: &'static [&'static str], visitor: V)
+    -> Result<V::Value> {
+        de::Deserializer::deserialize_tuple(self, fields.len(), visitor)
+    }
+}
@@ -0,0 +1,220 @@
+//! Contains size related definitions.
+//!
+//! The size of a value can be computed with `size_of`, while
+//! `fixed_size` gives the size of a parser whose layout doesn't
+//! depend on the data.
+//!
+//! Parsers with a fixed layout report their size with `fixed`,
+//! the ones in `numbers` do it with the size of their encoding,
+//! while `array`, `count` and `seq!` sum the sizes of their
+//! parts.
+//!
+//! # Examples
+//! ```
+//! use bin_io::numbers::{ be_u16, be_u24 };
+//! use bin_io::strings::null_utf8;
+//! use bin_io::{ array, fixed_size };
+//!
+//! assert_eq!(fixed_size(be_u24()), Some(3));
+//! assert_eq!(fixed_size::<_, _, [u16; 4]>(array(be_u16())), Some(8));
+//! assert_eq!(fixed_size(null_utf8()), None);
+//! ```
+
+use std::io::{ self, Read, Write, Error, ErrorKind };
+use std::cell::Cell;
+
+/// Writer that discards everything, counting
+/// the number of bytes written.
+///
+/// This is used by `size_of`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SizeCounter {
+    count: usize
+}
+
+impl SizeCounter {
+    pub fn new() -> Self {
+        Self { count: 0 }
+    }
+
+    /// Returns the number of bytes written so far.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+impl Write for SizeCounter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.count += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Reader used by `fixed_size`.
+///
+/// It doesn't contain any data: reading from it fails,
+/// so only the parsers reporting their size with `fixed`
+/// can be measured.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SizeQuery;
+
+impl Read for SizeQuery {
+    fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+        Err(not_fixed())
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Mode {
+    Off,
+    // Sum of the sizes reported while reading a value,
+    // `None` if a part of it is variable
+    Measure(Option<usize>),
+    // Sum of the sizes reported by `fixed_size`,
+    // together with the value parsers must fake
+    Query(usize, u8)
+}
+
+thread_local! {
+    static MODE: Cell<Mode> = const { Cell::new(Mode::Off) };
+}
+
+/// Restores the previous mode when dropped.
+struct ModeGuard(Mode);
+
+impl ModeGuard {
+    fn enter(mode: Mode) -> Self {
+        ModeGuard(MODE.with(|m| m.replace(mode)))
+    }
+}
+
+impl Drop for ModeGuard {
+    fn drop(&mut self) {
+        MODE.with(|m| m.set(self.0));
+    }
+}
+
+fn not_fixed() -> Error {
+    Error::new(ErrorKind::Other, "The size depends on the data")
+}
+
+/// Reports that the calling parser always reads `size` bytes.
+///
+/// Parsers with a fixed layout call this before reading.
+///
+/// # Remarks
+/// While `fixed_size` is running the function returns a
+/// made up value (`0` or `1`) and the parser must return it,
+/// converted to its type, without touching the reader.
+///
+/// # Examples
+/// ```
+/// use std::io::{ self, Read, Write };
+/// use bin_io::{ ReadFn, WriteFn, fixed_size };
+/// use bin_io::size;
+///
+/// fn rgb<R: Read + ?Sized, W: Write + ?Sized>()
+/// -> (impl ReadFn<R, [u8; 3]>, impl WriteFn<W, [u8; 3]>) {
+///     (|r: &mut R| {
+///         let mut v = [0; 3];
+///
+///         match size::fixed(3) {
+///             Some(b) => v = [b; 3],
+///             None => r.read_exact(&mut v)?
+///         }
+///
+///         Ok(v)
+///     },
+///     |w: &mut W, v: &[u8; 3]| w.write_all(v))
+/// }
+///
+/// assert_eq!(fixed_size(rgb()), Some(3));
+/// ```
+pub fn fixed(size: usize) -> Option<u8> {
+    MODE.with(|m| match m.get() {
+        Mode::Off => None,
+        Mode::Measure(s) => {
+            m.set(Mode::Measure(s.map(|s| s + size)));
+            None
+        },
+        Mode::Query(s, b) => {
+            m.set(Mode::Query(s + size, b));
+            Some(b)
+        }
+    })
+}
+
+/// Reports that the size of the calling parser
+/// depends on the data.
+///
+/// Parsers which read something only if a condition
+/// holds call this before reading.
+///
+/// # Errors
+/// While `fixed_size` is running an error is returned,
+/// which the parser should return right away.
+pub fn variable() -> io::Result<()> {
+    MODE.with(|m| match m.get() {
+        Mode::Off => Ok(()),
+        Mode::Measure(_) => {
+            m.set(Mode::Measure(None));
+            Ok(())
+        },
+        Mode::Query(_, _) => Err(not_fixed())
+    })
+}
+
+/// Returns `true` while `fixed_size` is running.
+///
+/// Parsers checking the values they read (like `bind`) use
+/// this to skip the check, since the values are made up.
+pub(crate) fn querying() -> bool {
+    MODE.with(|m| matches!(m.get(), Mode::Query(_, _)))
+}
+
+/// Calls `f`, returning the sum of the sizes
+/// reported in the meantime.
+///
+/// The sizes are also added to the ones of
+/// an outer `measure`.
+pub(crate) fn measure<T>(f: impl FnOnce() -> T) -> (T, Option<usize>) {
+    let outer = MODE.with(|m| m.get());
+
+    let (t, size) = match outer {
+        Mode::Query(_, _) => (f(), None),
+        _ => {
+            let guard = ModeGuard::enter(Mode::Measure(Some(0)));
+            let t = f();
+            let size = match MODE.with(|m| m.get()) {
+                Mode::Measure(s) => s,
+                _ => None
+            };
+
+            drop(guard);
+            (t, size)
+        }
+    };
+
+    if let Mode::Measure(Some(o)) = outer {
+        MODE.with(|m| m.set(Mode::Measure(size.map(|s| o + s))));
+    }
+
+    (t, size)
+}
+
+/// Reads from a `SizeQuery` pretending every value is `fake`,
+/// returning the sum of the sizes reported.
+pub(crate) fn query<T>(fake: u8, f: impl FnOnce(&mut SizeQuery) -> io::Result<T>) -> Option<usize> {
+    let _guard = ModeGuard::enter(Mode::Query(0, fake));
+
+    f(&mut SizeQuery).ok()?;
+
+    match MODE.with(|m| m.get()) {
+        Mode::Query(s, _) => Some(s),
+        _ => None
+    }
+}
@@ -1,9 +1,35 @@
 //! Contains string related functions.
 
-use crate::{ WriteFn, ReadFn, read, write, BinError };
-use std::io::{ Read, Write, Error };
+use crate::{ WriteFn, ReadFn, read, write, try_cast, read_len_bytes, BinError, MAX_PREALLOC };
+use crate::numbers::Endian;
+use std::io::{ self, Read, Write, Error };
+use std::ffi::CString;
+use std::convert::{ TryFrom, TryInto };
 
-use byteorder::{ ReadBytesExt, WriteBytesExt, BigEndian };
+use byteorder::{ ReadBytesExt, WriteBytesExt, ByteOrder, BigEndian, LittleEndian };
+
+/// Length of a string, either in bytes 
+/// or in code units.
+/// 
+/// # Errors
+/// A length in bytes must be a multiple of the size of
+/// a code unit, otherwise reading and writing fail with
+/// `BinError::LengthMismatch`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Len {
+    Bytes(usize),
+    Units(usize)
+}
+
+impl Len {
+    fn units(self, size: usize) -> io::Result<usize> {
+        match self {
+            Len::Bytes(len) if len % size != 0 => Err(Error::from(BinError::LengthMismatch)),
+            Len::Bytes(len) => Ok(len / size),
+            Len::Units(len) => Ok(len)
+        }
+    }
+}
 
 /// Reads/Writes a null terminated ascii string from a stream.
 /// 
@@ -32,7 +58,7 @@ use byteorder::{ ReadBytesExt, WriteBytesExt, BigEndian };
 /// 
 /// assert_eq!(string, "Foo");
 /// ```
-pub fn null_ascii<R: Read, W: Write>() 
+pub fn null_ascii<R: Read + ?Sized, W: Write + ?Sized>() 
 -> (impl ReadFn<R, String>, impl WriteFn<W, String>) {
 
     (|r: &mut R| {
@@ -78,7 +104,7 @@ pub fn null_ascii<R: Read, W: Write>()
 /// 
 /// assert_eq!(string, "Bar");
 /// ```
-pub fn len_ascii<R: Read, W: Write>(len: usize) 
+pub fn len_ascii<R: Read + ?Sized, W: Write + ?Sized>(len: usize) 
 -> (impl ReadFn<R, String>, impl WriteFn<W, String>) {
 
     (move |r: &mut R| {
@@ -119,25 +145,18 @@ pub fn len_ascii<R: Read, W: Write>(len: usize)
 /// 
 /// assert_eq!(string, "🦀");
 /// ```
-pub fn null_utf8<R: Read, W: Write>() 
+pub fn null_utf8<R: Read + ?Sized, W: Write + ?Sized>() 
 -> (impl ReadFn<R, String>, impl WriteFn<W, String>) {
 
     (|r: &mut R| {
-        let mut s = Vec::new();
-        loop {
-            let c = r.read_u8()?;
-            match c {
-                0 => break,
-                _ => s.push(c)
-            }
-        }
+        let s = read_null_bytes(r, None)?;
 
         String::from_utf8(s)
             .map_err(|e| Error::from(BinError::from(e)))
     },
     |w: &mut W, s: &String| {
 
-        w.write_all(&s.as_bytes()[..])
+        w.write_all(s.as_bytes())
     })
 }
 
@@ -163,7 +182,7 @@ pub fn null_utf8<R: Read, W: Write>()
 /// 
 /// assert_eq!(string, "🦀");
 /// ```
-pub fn len_utf8<R: Read, W: Write>(len: usize) 
+pub fn len_utf8<R: Read + ?Sized, W: Write + ?Sized>(len: usize) 
 -> (impl ReadFn<R, String>, impl WriteFn<W, String>) {
 
     (move |r: &mut R| {
@@ -176,7 +195,7 @@ pub fn len_utf8<R: Read, W: Write>(len: usize)
     move |w: &mut W, s: &String| {
 
         match s.len() == len {
-            true => w.write_all(&s.as_bytes()[..]),
+            true => w.write_all(s.as_bytes()),
             false => panic!("String's length is invalid")
         }
     })
@@ -184,6 +203,9 @@ pub fn len_utf8<R: Read, W: Write>(len: usize)
 
 /// Reads/Writes a null terminated utf16 string from a stream.
 /// 
+/// # Remarks
+/// This is the same as `be_null_utf16`.
+/// 
 /// # Examples
 /// ```
 /// use std::io::Cursor;
@@ -198,71 +220,1529 @@ pub fn len_utf8<R: Read, W: Write>(len: usize)
 /// 
 /// assert_eq!(string, "💖");
 /// ```
-pub fn null_utf16<R: Read, W: Write>() 
+pub fn null_utf16<R: Read + ?Sized, W: Write + ?Sized>() 
 -> (impl ReadFn<R, String>, impl WriteFn<W, String>) {
 
-    (|r: &mut R| {
-        let mut s = Vec::new();
-        loop {
-            let c = r.read_u16::<BigEndian>()?;
-            match c {
-                0 => break,
-                _ => s.push(c)
+    be_null_utf16()
+}
+
+/// Reads/Writes an utf16 string from a stream given its length.
+/// 
+/// # Remarks
+/// This is the same as `be_len_utf16`, with
+/// the length expressed in bytes.
+/// 
+/// # Errors
+/// If the length of the input string is not `len` 
+/// an error is returned.
+/// 
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::strings::len_utf16;
+/// use bin_io::read;
+/// 
+/// let vec = vec![ 0xd8, 0x3d, 0xdc, 0x96 ];
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// let string = read(&mut cursor, len_utf16(4))
+///     .unwrap();
+/// 
+/// assert_eq!(string, "💖");
+/// ```
+pub fn len_utf16<R: Read + ?Sized, W: Write + ?Sized>(len: usize) 
+-> (impl ReadFn<R, String>, impl WriteFn<W, String>) {
+
+    be_len_utf16(Len::Bytes(len))
+}
+
+fn read_null_bytes<R: Read + ?Sized>(r: &mut R, max: Option<usize>)
+-> io::Result<Vec<u8>> {
+    let mut s = Vec::new();
+    loop {
+        if let Some(max) = max {
+            if s.len() >= max {
+                return Err(Error::from(BinError::MissingTerminator(max)));
+            }
+        }
+
+        let c = r.read_u8()?;
+        match c {
+            0 => break,
+            _ => s.push(c)
+        }
+    }
+
+    Ok(s)
+}
+
+fn read_null_utf16<B: ByteOrder, R: Read + ?Sized>(r: &mut R, mut first: Option<u16>, max: Option<usize>)
+-> io::Result<String> {
+    let mut s = Vec::new();
+    loop {
+        if let Some(max) = max {
+            if (s.len() + 1) * 2 > max {
+                return Err(Error::from(BinError::MissingTerminator(max)));
             }
         }
 
+        let c = match first.take() {
+            Some(c) => c,
+            None => r.read_u16::<B>()?
+        };
+
+        match c {
+            0 => break,
+            _ => s.push(c)
+        }
+    }
+
+    String::from_utf16(&s[..])
+        .map_err(|e| Error::from(BinError::from(e)))
+}
+
+fn read_len_u16<B: ByteOrder, R: Read + ?Sized>(r: &mut R, units: usize)
+-> io::Result<Vec<u16>> {
+    let bytes = units.checked_mul(2)
+        .ok_or_else(|| Error::from(BinError::OutOfRange))?;
+
+    let b = read_len_bytes(r, bytes)?;
+    let mut s = vec![0; units];
+    B::read_u16_into(&b[..], &mut s[..]);
+
+    Ok(s)
+}
+
+fn read_len_utf16<B: ByteOrder, R: Read + ?Sized>(r: &mut R, units: usize)
+-> io::Result<String> {
+    let s = read_len_u16::<B, _>(r, units)?;
+
+    String::from_utf16(&s[..])
+        .map_err(|e| Error::from(BinError::from(e)))
+}
+
+fn write_utf16<B: ByteOrder, W: Write + ?Sized>(w: &mut W, s: &str) 
+-> io::Result<()> {
+    for c in s.encode_utf16() {
+        w.write_u16::<B>(c)?;
+    }
+
+    Ok(())
+}
+
+fn utf32_char(c: u32) -> io::Result<char> {
+    std::char::from_u32(c)
+        .ok_or_else(|| Error::from(BinError::Utf32Conversion))
+}
+
+fn read_null_utf32<B: ByteOrder, R: Read + ?Sized>(r: &mut R, mut first: Option<u32>)
+-> io::Result<String> {
+    let mut s = String::new();
+    loop {
+        let c = match first.take() {
+            Some(c) => c,
+            None => r.read_u32::<B>()?
+        };
+
+        match c {
+            0 => break,
+            _ => s.push(utf32_char(c)?)
+        }
+    }
+
+    Ok(s)
+}
+
+fn read_len_utf32<B: ByteOrder, R: Read + ?Sized>(r: &mut R, units: usize)
+-> io::Result<String> {
+    let mut s = String::with_capacity(units.min(MAX_PREALLOC));
+    for _ in 0..units {
+        s.push(utf32_char(r.read_u32::<B>()?)?);
+    }
+
+    Ok(s)
+}
+
+fn write_utf32<B: ByteOrder, W: Write + ?Sized>(w: &mut W, s: &str) 
+-> io::Result<()> {
+    for c in s.chars() {
+        w.write_u32::<B>(c as u32)?;
+    }
+
+    Ok(())
+}
+
+fn null_utf16_with<B: ByteOrder, R: Read + ?Sized, W: Write + ?Sized>() 
+-> (impl ReadFn<R, String>, impl WriteFn<W, String>) {
+
+    (|r: &mut R| {
+        read_null_utf16::<B, _>(r, None, None)
+    },
+    |w: &mut W, s: &String| {
+        write_utf16::<B, _>(w, s)?;
+        w.write_u16::<B>(0)
+    })
+}
+
+fn null_utf16_max_with<B: ByteOrder, R: Read + ?Sized, W: Write + ?Sized>(max: usize) 
+-> (impl ReadFn<R, String>, impl WriteFn<W, String>) {
+
+    (move |r: &mut R| {
+        read_null_utf16::<B, _>(r, None, Some(max))
+    },
+    move |w: &mut W, s: &String| {
+        match (s.encode_utf16().count() + 1) * 2 <= max {
+            true => write(w, s, null_utf16_with::<B, _, _>()),
+            false => Err(Error::from(BinError::StringTooLong))
+        }
+    })
+}
+
+fn prefixed_utf16_with<B: ByteOrder, R: Read + ?Sized, W: Write + ?Sized, Rf, Wf, L>(len: (Rf, Wf)) 
+-> (impl ReadFn<R, String>, impl WriteFn<W, String>)
+where Rf: ReadFn<R, L>, Wf: WriteFn<W, L>, L: TryFrom<usize> + TryInto<usize> {
+
+    let (lr, lw) = try_cast(len);
+
+    (move |r: &mut R| {
+        let len = lr(r)?;
+        let bytes = len.checked_mul(2)
+            .ok_or_else(|| Error::from(BinError::OutOfRange))?;
+
+        let b = read_len_bytes(r, bytes)?;
+        let mut s = vec![0; len];
+        B::read_u16_into(&b[..], &mut s[..]);
+
         String::from_utf16(&s[..])
             .map_err(|e| Error::from(BinError::from(e)))
     },
-    |w: &mut W, s: &String| {
-        for c in s.encode_utf16() {
-            w.write_u16::<BigEndian>(c)?;
+    move |w: &mut W, s: &String| {
+        let len = s.encode_utf16().count();
+        lw(w, &len)?;
+        write_utf16::<B, _>(w, s)
+    })
+}
+
+fn len_utf16_with<B: ByteOrder, R: Read + ?Sized, W: Write + ?Sized>(len: Len) 
+-> (impl ReadFn<R, String>, impl WriteFn<W, String>) {
+
+    (move |r: &mut R| {
+        let units = len.units(2)?;
+        read_len_utf16::<B, _>(r, units)
+    },
+    move |w: &mut W, s: &String| {
+        let units = len.units(2)?;
+
+        match s.encode_utf16().count() == units {
+            true => write_utf16::<B, _>(w, s),
+            false => Err(Error::from(BinError::LengthMismatch))
         }
+    })
+}
+
+fn null_utf32_with<B: ByteOrder, R: Read + ?Sized, W: Write + ?Sized>() 
+-> (impl ReadFn<R, String>, impl WriteFn<W, String>) {
+
+    (|r: &mut R| {
+        read_null_utf32::<B, _>(r, None)
+    },
+    |w: &mut W, s: &String| {
+        write_utf32::<B, _>(w, s)?;
+        w.write_u32::<B>(0)
+    })
+}
+
+fn len_utf32_with<B: ByteOrder, R: Read + ?Sized, W: Write + ?Sized>(len: Len) 
+-> (impl ReadFn<R, String>, impl WriteFn<W, String>) {
+
+    (move |r: &mut R| {
+        let units = len.units(4)?;
+        read_len_utf32::<B, _>(r, units)
+    },
+    move |w: &mut W, s: &String| {
+        let units = len.units(4)?;
 
-        w.write_u16::<BigEndian>(0)
+        match s.chars().count() == units {
+            true => write_utf32::<B, _>(w, s),
+            false => Err(Error::from(BinError::LengthMismatch))
+        }
     })
 }
 
-/// Reads/Writes an utf16 string from a stream given its length.
+/// Reads/Writes a null terminated big endian utf16 string from a stream.
+pub fn be_null_utf16<R: Read + ?Sized, W: Write + ?Sized>() 
+-> (impl ReadFn<R, String>, impl WriteFn<W, String>) {
+
+    null_utf16_with::<BigEndian, _, _>()
+}
+
+/// Reads/Writes a null terminated little endian utf16 string from a stream.
 /// 
 /// # Examples
 /// ```
 /// use std::io::Cursor;
-/// use bin_io::strings::len_utf16;
+/// use bin_io::strings::le_null_utf16;
 /// use bin_io::read;
 /// 
-/// let vec = vec![ 0xd8, 0x3d, 0xdc, 0x96 ];
+/// let vec = vec![ 0x3d, 0xd8, 0x96, 0xdc, 0x00, 0x00 ];
 /// let mut cursor = Cursor::new(vec);
 /// 
-/// let string = read(&mut cursor, len_utf16(4))
+/// let string = read(&mut cursor, le_null_utf16())
 ///     .unwrap();
 /// 
 /// assert_eq!(string, "💖");
 /// ```
-pub fn len_utf16<R: Read, W: Write>(len: usize) 
+pub fn le_null_utf16<R: Read + ?Sized, W: Write + ?Sized>() 
+-> (impl ReadFn<R, String>, impl WriteFn<W, String>) {
+
+    null_utf16_with::<LittleEndian, _, _>()
+}
+
+/// Reads/Writes a big endian utf16 string from a stream given its length.
+/// 
+/// # Errors
+/// If the length of the input string is not `len` 
+/// an error is returned.
+pub fn be_len_utf16<R: Read + ?Sized, W: Write + ?Sized>(len: Len) 
+-> (impl ReadFn<R, String>, impl WriteFn<W, String>) {
+
+    len_utf16_with::<BigEndian, _, _>(len)
+}
+
+/// Reads/Writes a little endian utf16 string from a stream given its length.
+/// 
+/// # Errors
+/// If the length of the input string is not `len` 
+/// an error is returned.
+/// 
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::strings::{ le_len_utf16, Len };
+/// use bin_io::{ read, write };
+/// 
+/// let vec = Vec::new();
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// write(&mut cursor, &"Foo".to_owned(), le_len_utf16(Len::Units(3)))
+///     .unwrap();
+/// 
+/// assert_eq!(cursor.get_ref(), &[ 0x46, 0x00, 0x6f, 0x00, 0x6f, 0x00 ]);
+/// 
+/// cursor.set_position(0);
+/// 
+/// let string = read(&mut cursor, le_len_utf16(Len::Bytes(6)))
+///     .unwrap();
+/// 
+/// assert_eq!(string, "Foo");
+/// 
+/// cursor.set_position(0);
+/// 
+/// // Not a whole number of code units
+/// let err = read(&mut cursor, le_len_utf16(Len::Bytes(5)));
+/// 
+/// assert!(err.is_err());
+/// ```
+pub fn le_len_utf16<R: Read + ?Sized, W: Write + ?Sized>(len: Len) 
+-> (impl ReadFn<R, String>, impl WriteFn<W, String>) {
+
+    len_utf16_with::<LittleEndian, _, _>(len)
+}
+
+/// Reads/Writes a null terminated utf16 string 
+/// starting with a BOM from a stream.
+/// 
+/// ## Reading
+/// The endianness is picked from the BOM, if the BOM
+/// is missing the passed endianness is used.
+/// 
+/// ## Writing
+/// The function writes the BOM followed by the string,
+/// using the passed endianness.
+/// 
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::numbers::Endian;
+/// use bin_io::strings::bom_null_utf16;
+/// use bin_io::{ read, write };
+/// 
+/// let vec = Vec::new();
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// write(&mut cursor, &"Hi".to_owned(), bom_null_utf16(Endian::Little))
+///     .unwrap();
+/// 
+/// assert_eq!(cursor.get_ref(), &[ 0xff, 0xfe, 0x48, 0x00, 0x69, 0x00, 0x00, 0x00 ]);
+/// 
+/// cursor.set_position(0);
+/// 
+/// // The endianness is picked from the BOM
+/// let string = read(&mut cursor, bom_null_utf16(Endian::Big))
+///     .unwrap();
+/// 
+/// assert_eq!(string, "Hi");
+/// ```
+pub fn bom_null_utf16<R: Read + ?Sized, W: Write + ?Sized>(endian: Endian) 
+-> (impl ReadFn<R, String>, impl WriteFn<W, String>) {
+
+    (move |r: &mut R| {
+        match (r.read_u16::<BigEndian>()?, endian) {
+            (0xfeff, _) => read_null_utf16::<BigEndian, _>(r, None, None),
+            (0xfffe, _) => read_null_utf16::<LittleEndian, _>(r, None, None),
+            (c, Endian::Big) => read_null_utf16::<BigEndian, _>(r, Some(c), None),
+            (c, Endian::Little) => read_null_utf16::<LittleEndian, _>(r, Some(c.swap_bytes()), None)
+        }
+    },
+    move |w: &mut W, s: &String| {
+        match endian {
+            Endian::Big => {
+                w.write_u16::<BigEndian>(0xfeff)?;
+                write(w, s, be_null_utf16())
+            },
+            Endian::Little => {
+                w.write_u16::<LittleEndian>(0xfeff)?;
+                write(w, s, le_null_utf16())
+            }
+        }
+    })
+}
+
+/// Reads/Writes an utf16 string starting with a BOM 
+/// from a stream given its length.
+/// 
+/// The length includes the BOM.
+/// 
+/// ## Reading
+/// The endianness is picked from the BOM, if the BOM
+/// is missing the passed endianness is used.
+/// 
+/// ## Writing
+/// The function writes the BOM followed by the string,
+/// using the passed endianness. If `len` is 0 nothing
+/// is written.
+/// 
+/// # Errors
+/// If the length of the input string (BOM included)
+/// is not `len` an error is returned.
+/// 
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::numbers::Endian;
+/// use bin_io::strings::{ bom_len_utf16, Len };
+/// use bin_io::{ read, write };
+/// 
+/// let vec = Vec::new();
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// write(&mut cursor, &String::new(), bom_len_utf16(Endian::Big, Len::Units(0)))
+///     .unwrap();
+/// 
+/// assert!(cursor.get_ref().is_empty());
+/// 
+/// let err = write(&mut cursor, &"AB".to_owned(), bom_len_utf16(Endian::Big, Len::Units(2)));
+/// 
+/// assert!(err.is_err());
+/// ```
+pub fn bom_len_utf16<R: Read + ?Sized, W: Write + ?Sized>(endian: Endian, len: Len) 
 -> (impl ReadFn<R, String>, impl WriteFn<W, String>) {
 
     (move |r: &mut R| {
-        let mut s = Vec::new();
-        for _ in (0..len).step_by(2) {
-            let c = r.read_u16::<BigEndian>()?;
-            s.push(c);
+        let units = len.units(2)?;
+
+        if units == 0 {
+            return Ok(String::new());
         }
 
+        let (big, first) = match (r.read_u16::<BigEndian>()?, endian) {
+            (0xfeff, _) => (true, None),
+            (0xfffe, _) => (false, None),
+            (c, Endian::Big) => (true, Some(c)),
+            (c, Endian::Little) => (false, Some(c.swap_bytes()))
+        };
+
+        let rest = match big {
+            true => read_len_u16::<BigEndian, _>(r, units - 1)?,
+            false => read_len_u16::<LittleEndian, _>(r, units - 1)?
+        };
+
+        let s: Vec<u16> = first.into_iter()
+            .chain(rest)
+            .collect();
+
         String::from_utf16(&s[..])
             .map_err(|e| Error::from(BinError::from(e)))
     },
     move |w: &mut W, s: &String| {
-        match s.len() == len {
-            true => {
-                for c in s.encode_utf16() {
-                    w.write_u16::<BigEndian>(c)?;
-                }
+        let units = len.units(2)?;
 
-                Ok(())
-            },
-            false => panic!("String's length is invalid")
+        if units == 0 && s.is_empty() {
+            return Ok(());
         }
 
+        match (s.encode_utf16().count() + 1 == units, endian) {
+            (true, Endian::Big) => {
+                w.write_u16::<BigEndian>(0xfeff)?;
+                write_utf16::<BigEndian, _>(w, s)
+            },
+            (true, Endian::Little) => {
+                w.write_u16::<LittleEndian>(0xfeff)?;
+                write_utf16::<LittleEndian, _>(w, s)
+            },
+            (false, _) => Err(Error::from(BinError::LengthMismatch))
+        }
     })
-}
\ No newline at end of file
+}
+
+/// Reads/Writes a null terminated big endian utf32 string from a stream.
+/// 
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::strings::be_null_utf32;
+/// use bin_io::read;
+/// 
+/// let vec = vec![ 0x00, 0x01, 0xf9, 0x80, 0x00, 0x00, 0x00, 0x00 ];
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// let string = read(&mut cursor, be_null_utf32())
+///     .unwrap();
+/// 
+/// assert_eq!(string, "🦀");
+/// ```
+pub fn be_null_utf32<R: Read + ?Sized, W: Write + ?Sized>() 
+-> (impl ReadFn<R, String>, impl WriteFn<W, String>) {
+
+    null_utf32_with::<BigEndian, _, _>()
+}
+
+/// Reads/Writes a null terminated little endian utf32 string from a stream.
+pub fn le_null_utf32<R: Read + ?Sized, W: Write + ?Sized>() 
+-> (impl ReadFn<R, String>, impl WriteFn<W, String>) {
+
+    null_utf32_with::<LittleEndian, _, _>()
+}
+
+/// Reads/Writes a big endian utf32 string from a stream given its length.
+/// 
+/// # Errors
+/// If the length of the input string is not `len` 
+/// an error is returned.
+pub fn be_len_utf32<R: Read + ?Sized, W: Write + ?Sized>(len: Len) 
+-> (impl ReadFn<R, String>, impl WriteFn<W, String>) {
+
+    len_utf32_with::<BigEndian, _, _>(len)
+}
+
+/// Reads/Writes a little endian utf32 string from a stream given its length.
+/// 
+/// # Errors
+/// If the length of the input string is not `len` 
+/// an error is returned.
+/// 
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::strings::{ le_len_utf32, Len };
+/// use bin_io::read;
+/// 
+/// let vec = vec![ 0x80, 0xf9, 0x01, 0x00, 0x21, 0x00, 0x00, 0x00 ];
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// let string = read(&mut cursor, le_len_utf32(Len::Units(2)))
+///     .unwrap();
+/// 
+/// assert_eq!(string, "🦀!");
+/// ```
+pub fn le_len_utf32<R: Read + ?Sized, W: Write + ?Sized>(len: Len) 
+-> (impl ReadFn<R, String>, impl WriteFn<W, String>) {
+
+    len_utf32_with::<LittleEndian, _, _>(len)
+}
+
+/// Reads/Writes a null terminated utf32 string 
+/// starting with a BOM from a stream.
+/// 
+/// ## Reading
+/// The endianness is picked from the BOM, if the BOM
+/// is missing the passed endianness is used.
+/// 
+/// ## Writing
+/// The function writes the BOM followed by the string,
+/// using the passed endianness.
+pub fn bom_null_utf32<R: Read + ?Sized, W: Write + ?Sized>(endian: Endian) 
+-> (impl ReadFn<R, String>, impl WriteFn<W, String>) {
+
+    (move |r: &mut R| {
+        match (r.read_u32::<BigEndian>()?, endian) {
+            (0x0000_feff, _) => read_null_utf32::<BigEndian, _>(r, None),
+            (0xfffe_0000, _) => read_null_utf32::<LittleEndian, _>(r, None),
+            (c, Endian::Big) => read_null_utf32::<BigEndian, _>(r, Some(c)),
+            (c, Endian::Little) => read_null_utf32::<LittleEndian, _>(r, Some(c.swap_bytes()))
+        }
+    },
+    move |w: &mut W, s: &String| {
+        match endian {
+            Endian::Big => {
+                w.write_u32::<BigEndian>(0xfeff)?;
+                write(w, s, be_null_utf32())
+            },
+            Endian::Little => {
+                w.write_u32::<LittleEndian>(0xfeff)?;
+                write(w, s, le_null_utf32())
+            }
+        }
+    })
+}
+
+/// Reads/Writes an utf32 string starting with a BOM 
+/// from a stream given its length.
+/// 
+/// The length includes the BOM.
+/// 
+/// ## Reading
+/// The endianness is picked from the BOM, if the BOM
+/// is missing the passed endianness is used.
+/// 
+/// ## Writing
+/// The function writes the BOM followed by the string,
+/// using the passed endianness. If `len` is 0 nothing
+/// is written.
+/// 
+/// # Errors
+/// If the length of the input string (BOM included)
+/// is not `len` an error is returned.
+/// 
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::numbers::Endian;
+/// use bin_io::strings::{ bom_len_utf32, Len };
+/// use bin_io::{ read, write };
+/// 
+/// let vec = Vec::new();
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// write(&mut cursor, &"A".to_owned(), bom_len_utf32(Endian::Big, Len::Bytes(8)))
+///     .unwrap();
+/// 
+/// assert_eq!(cursor.get_ref(), &[ 0x00, 0x00, 0xfe, 0xff, 0x00, 0x00, 0x00, 0x41 ]);
+/// 
+/// cursor.set_position(0);
+/// 
+/// let string = read(&mut cursor, bom_len_utf32(Endian::Little, Len::Units(2)))
+///     .unwrap();
+/// 
+/// assert_eq!(string, "A");
+/// ```
+pub fn bom_len_utf32<R: Read + ?Sized, W: Write + ?Sized>(endian: Endian, len: Len) 
+-> (impl ReadFn<R, String>, impl WriteFn<W, String>) {
+
+    (move |r: &mut R| {
+        let units = len.units(4)?;
+
+        if units == 0 {
+            return Ok(String::new());
+        }
+
+        let (big, mut s) = match (r.read_u32::<BigEndian>()?, endian) {
+            (0x0000_feff, _) => (true, String::new()),
+            (0xfffe_0000, _) => (false, String::new()),
+            (c, Endian::Big) => (true, utf32_char(c)?.to_string()),
+            (c, Endian::Little) => (false, utf32_char(c.swap_bytes())?.to_string())
+        };
+
+        s.push_str(&match big {
+            true => read_len_utf32::<BigEndian, _>(r, units - 1)?,
+            false => read_len_utf32::<LittleEndian, _>(r, units - 1)?
+        });
+
+        Ok(s)
+    },
+    move |w: &mut W, s: &String| {
+        let units = len.units(4)?;
+
+        if units == 0 && s.is_empty() {
+            return Ok(());
+        }
+
+        match (s.chars().count() + 1 == units, endian) {
+            (true, Endian::Big) => {
+                w.write_u32::<BigEndian>(0xfeff)?;
+                write_utf32::<BigEndian, _>(w, s)
+            },
+            (true, Endian::Little) => {
+                w.write_u32::<LittleEndian>(0xfeff)?;
+                write_utf32::<LittleEndian, _>(w, s)
+            },
+            (false, _) => Err(Error::from(BinError::LengthMismatch))
+        }
+    })
+}
+
+fn fixed_utf8_with<R: Read + ?Sized, W: Write + ?Sized>(size: usize, pad: u8, null: bool) 
+-> (impl ReadFn<R, String>, impl WriteFn<W, String>) {
+
+    (move |r: &mut R| {
+        let mut s = vec![0; size];
+        r.read_exact(&mut s[..])?;
+
+        match (s.iter().position(|b| *b == 0), null) {
+            (Some(end), _) => s.truncate(end),
+            (None, true) => return Err(Error::from(BinError::CheckFail)),
+            (None, false) => {}
+        }
+
+        while s.last() == Some(&pad) {
+            s.pop();
+        }
+
+        String::from_utf8(s)
+            .map_err(|e| Error::from(BinError::from(e)))
+    },
+    move |w: &mut W, s: &String| {
+        let mut s = s.as_bytes().to_vec();
+
+        if null {
+            s.push(0);
+        }
+
+        match s.len() <= size {
+            true => {
+                s.resize(size, pad);
+                w.write_all(&s[..])
+            },
+            false => Err(Error::from(BinError::StringTooLong))
+        }
+    })
+}
+
+/// Reads/Writes an utf8 string from a fixed size slot of `size` bytes.
+/// 
+/// ## Reading
+/// The function reads the whole slot, then cuts the
+/// string at the first null byte (if any) and removes 
+/// the trailing `pad` bytes.
+/// 
+/// ## Writing
+/// The function writes the string, and fills the rest
+/// of the slot with `pad`. If the string is longer than
+/// `size` an error is returned.
+/// 
+/// # Remarks
+/// Trailing `pad` bytes in the input string are lost 
+/// when reading it back.
+/// 
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::strings::fixed_utf8;
+/// use bin_io::{ read, write };
+/// 
+/// let vec = Vec::new();
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// // A FAT 8.3 name
+/// write(&mut cursor, &"README".to_owned(), fixed_utf8(8, b' '))
+///     .unwrap();
+/// 
+/// assert_eq!(cursor.get_ref(), b"README  ");
+/// 
+/// cursor.set_position(0);
+/// 
+/// let string = read(&mut cursor, fixed_utf8(8, b' '))
+///     .unwrap();
+/// 
+/// assert_eq!(string, "README");
+/// 
+/// // Too long!
+/// let err = write(&mut cursor, &"LONGFILENAME".to_owned(), fixed_utf8(8, b' '));
+/// 
+/// assert!(err.is_err());
+/// ```
+pub fn fixed_utf8<R: Read + ?Sized, W: Write + ?Sized>(size: usize, pad: u8) 
+-> (impl ReadFn<R, String>, impl WriteFn<W, String>) {
+
+    fixed_utf8_with(size, pad, false)
+}
+
+/// Reads/Writes a null terminated utf8 string 
+/// from a fixed size slot of `size` bytes.
+/// 
+/// ## Reading
+/// The function reads the whole slot, then cuts the
+/// string at the first null byte and removes the 
+/// trailing `pad` bytes. If the slot doesn't contain 
+/// a null byte an error is returned.
+/// 
+/// ## Writing
+/// The function writes the string followed by a null 
+/// byte, and fills the rest of the slot with `pad`. If 
+/// the string (null byte included) is longer than `size`
+/// an error is returned.
+/// 
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::strings::fixed_null_utf8;
+/// use bin_io::{ read, write };
+/// 
+/// let vec = Vec::new();
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// write(&mut cursor, &"Foo".to_owned(), fixed_null_utf8(6, 0))
+///     .unwrap();
+/// 
+/// assert_eq!(cursor.get_ref(), &[ 0x46, 0x6f, 0x6f, 0x00, 0x00, 0x00 ]);
+/// 
+/// // No space left for the null byte
+/// let err = write(&mut cursor, &"Foobar".to_owned(), fixed_null_utf8(6, 0));
+/// 
+/// assert!(err.is_err());
+/// ```
+pub fn fixed_null_utf8<R: Read + ?Sized, W: Write + ?Sized>(size: usize, pad: u8) 
+-> (impl ReadFn<R, String>, impl WriteFn<W, String>) {
+
+    fixed_utf8_with(size, pad, true)
+}
+
+/// Reads/Writes an ascii string from a fixed size slot of `size` bytes.
+/// 
+/// # Remarks
+/// The only difference between `fixed_ascii` and
+/// `fixed_utf8` is the ascii check that fails if the 
+/// string is not ascii.
+/// 
+/// # Errors
+/// If the input string is not ascii an error is returned.
+/// 
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::strings::fixed_ascii;
+/// use bin_io::read;
+/// 
+/// // A PE section name
+/// let vec = vec![ 0x2e, 0x74, 0x65, 0x78, 0x74, 0x00, 0x00, 0x00 ];
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// let string = read(&mut cursor, fixed_ascii(8, 0))
+///     .unwrap();
+/// 
+/// assert_eq!(string, ".text");
+/// ```
+pub fn fixed_ascii<R: Read + ?Sized, W: Write + ?Sized>(size: usize, pad: u8) 
+-> (impl ReadFn<R, String>, impl WriteFn<W, String>) {
+
+    (move |r: &mut R| {
+        let s = read(r, fixed_utf8(size, pad))?;
+
+        match s.is_ascii() {
+            true => Ok(s),
+            false => Err(Error::from(BinError::CheckFail))
+        }
+    },
+    move |w: &mut W, s: &String| {
+        match s.is_ascii() {
+            true => write(w, s, fixed_utf8(size, pad)),
+            false => Err(Error::from(BinError::CheckFail))
+        }
+    })
+}
+
+/// Reads/Writes a null terminated ascii string 
+/// from a fixed size slot of `size` bytes.
+/// 
+/// # Remarks
+/// The only difference between `fixed_null_ascii` and
+/// `fixed_null_utf8` is the ascii check that fails if the 
+/// string is not ascii.
+/// 
+/// # Errors
+/// If the input string is not ascii an error is returned.
+pub fn fixed_null_ascii<R: Read + ?Sized, W: Write + ?Sized>(size: usize, pad: u8) 
+-> (impl ReadFn<R, String>, impl WriteFn<W, String>) {
+
+    (move |r: &mut R| {
+        let s = read(r, fixed_null_utf8(size, pad))?;
+
+        match s.is_ascii() {
+            true => Ok(s),
+            false => Err(Error::from(BinError::CheckFail))
+        }
+    },
+    move |w: &mut W, s: &String| {
+        match s.is_ascii() {
+            true => write(w, s, fixed_null_utf8(size, pad)),
+            false => Err(Error::from(BinError::CheckFail))
+        }
+    })
+}
+
+
+/// Reads/Writes a null terminated utf8 string from a stream,
+/// reading at most `max` bytes (null byte included).
+/// 
+/// ## Reading
+/// If no null byte is found in the first `max` bytes
+/// an error is returned.
+/// 
+/// ## Writing
+/// The string is written followed by the null byte.
+/// If the string (null byte included) is longer than
+/// `max` an error is returned.
+/// 
+/// # Remarks
+/// Unlike `null_utf8_max`, `null_utf8` doesn't write
+/// the null byte, so its output can't be read back by 
+/// itself. `null_utf8` keeps that behaviour for 
+/// compatibility, while `null_utf8_max` writes what it 
+/// reads.
+/// 
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::strings::null_utf8_max;
+/// use bin_io::{ read, write };
+/// 
+/// let vec = Vec::new();
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// write(&mut cursor, &"Foo".to_owned(), null_utf8_max(4))
+///     .unwrap();
+/// 
+/// assert_eq!(cursor.get_ref(), &[ 0x46, 0x6f, 0x6f, 0x00 ]);
+/// 
+/// cursor.set_position(0);
+/// 
+/// let string = read(&mut cursor, null_utf8_max(4))
+///     .unwrap();
+/// 
+/// assert_eq!(string, "Foo");
+/// 
+/// cursor.set_position(0);
+/// 
+/// // The null byte is too far
+/// let err = read(&mut cursor, null_utf8_max(3))
+///     .unwrap_err();
+/// 
+/// assert_eq!(err.to_string(), "No null terminator found in 3 bytes");
+/// ```
+pub fn null_utf8_max<R: Read + ?Sized, W: Write + ?Sized>(max: usize) 
+-> (impl ReadFn<R, String>, impl WriteFn<W, String>) {
+
+    (move |r: &mut R| {
+        let s = read_null_bytes(r, Some(max))?;
+
+        String::from_utf8(s)
+            .map_err(|e| Error::from(BinError::from(e)))
+    },
+    move |w: &mut W, s: &String| {
+        match s.len() < max {
+            true => {
+                w.write_all(s.as_bytes())?;
+                w.write_u8(0)
+            },
+            false => Err(Error::from(BinError::StringTooLong))
+        }
+    })
+}
+
+/// Reads/Writes a null terminated ascii string from a stream,
+/// reading at most `max` bytes (null byte included).
+/// 
+/// # Remarks
+/// The only difference between `null_ascii_max` and
+/// `null_utf8_max` is the ascii check that fails if the 
+/// string is not ascii.
+/// 
+/// # Errors
+/// If the input string is not ascii an error is returned.
+pub fn null_ascii_max<R: Read + ?Sized, W: Write + ?Sized>(max: usize) 
+-> (impl ReadFn<R, String>, impl WriteFn<W, String>) {
+
+    (move |r: &mut R| {
+        let s = read(r, null_utf8_max(max))?;
+
+        match s.is_ascii() {
+            true => Ok(s),
+            false => Err(Error::from(BinError::CheckFail))
+        }
+    },
+    move |w: &mut W, s: &String| {
+        match s.is_ascii() {
+            true => write(w, s, null_utf8_max(max)),
+            false => Err(Error::from(BinError::CheckFail))
+        }
+    })
+}
+
+/// Reads/Writes a null terminated utf16 string from a stream,
+/// reading at most `max` bytes (null terminator included).
+/// 
+/// # Remarks
+/// This is the same as `be_null_utf16_max`.
+pub fn null_utf16_max<R: Read + ?Sized, W: Write + ?Sized>(max: usize) 
+-> (impl ReadFn<R, String>, impl WriteFn<W, String>) {
+
+    be_null_utf16_max(max)
+}
+
+/// Reads/Writes a null terminated big endian utf16 string 
+/// from a stream, reading at most `max` bytes (null 
+/// terminator included).
+/// 
+/// ## Reading
+/// If no null terminator is found in the first `max` 
+/// bytes an error is returned.
+/// 
+/// ## Writing
+/// If the string (null terminator included) is longer 
+/// than `max` an error is returned.
+/// 
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::strings::be_null_utf16_max;
+/// use bin_io::read;
+/// 
+/// let vec = vec![ 0x00, 0x41, 0x00, 0x42, 0x00, 0x00 ];
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// // The terminator doesn't fit in 5 bytes
+/// let err = read(&mut cursor, be_null_utf16_max(5));
+/// 
+/// assert!(err.is_err());
+/// assert_eq!(cursor.position(), 4);
+/// ```
+pub fn be_null_utf16_max<R: Read + ?Sized, W: Write + ?Sized>(max: usize) 
+-> (impl ReadFn<R, String>, impl WriteFn<W, String>) {
+
+    null_utf16_max_with::<BigEndian, _, _>(max)
+}
+
+/// Reads/Writes a null terminated little endian utf16 string 
+/// from a stream, reading at most `max` bytes (null 
+/// terminator included).
+/// 
+/// ## Reading
+/// If no null terminator is found in the first `max` 
+/// bytes an error is returned.
+/// 
+/// ## Writing
+/// If the string (null terminator included) is longer 
+/// than `max` an error is returned.
+/// 
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::strings::le_null_utf16_max;
+/// use bin_io::read;
+/// 
+/// let vec = vec![ 0x41, 0x00, 0x42, 0x00, 0x43, 0x00 ];
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// let err = read(&mut cursor, le_null_utf16_max(4));
+/// 
+/// assert!(err.is_err());
+/// ```
+pub fn le_null_utf16_max<R: Read + ?Sized, W: Write + ?Sized>(max: usize) 
+-> (impl ReadFn<R, String>, impl WriteFn<W, String>) {
+
+    null_utf16_max_with::<LittleEndian, _, _>(max)
+}
+
+/// Reads/Writes a null terminated C string from a stream.
+/// 
+/// Unlike `null_utf8` the bytes are not 
+/// required to be valid utf8.
+/// 
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use std::ffi::CString;
+/// use bin_io::strings::null_cstr;
+/// use bin_io::{ read, write };
+/// 
+/// let vec = Vec::new();
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// let string = CString::new(vec![ 0xff, 0xfe ])
+///     .unwrap();
+/// 
+/// write(&mut cursor, &string, null_cstr())
+///     .unwrap();
+/// 
+/// assert_eq!(cursor.get_ref(), &[ 0xff, 0xfe, 0x00 ]);
+/// 
+/// cursor.set_position(0);
+/// 
+/// let other = read(&mut cursor, null_cstr())
+///     .unwrap();
+/// 
+/// assert_eq!(other, string);
+/// ```
+pub fn null_cstr<R: Read + ?Sized, W: Write + ?Sized>() 
+-> (impl ReadFn<R, CString>, impl WriteFn<W, CString>) {
+
+    (|r: &mut R| {
+        let s = read_null_bytes(r, None)?;
+
+        CString::new(s)
+            .map_err(|_| Error::from(BinError::CheckFail))
+    },
+    |w: &mut W, s: &CString| {
+
+        w.write_all(s.as_bytes_with_nul())
+    })
+}
+
+/// Reads/Writes a null terminated C string from a stream,
+/// reading at most `max` bytes (null byte included).
+/// 
+/// ## Reading
+/// If no null byte is found in the first `max` bytes
+/// an error is returned.
+/// 
+/// ## Writing
+/// If the string (null byte included) is longer than
+/// `max` an error is returned.
+pub fn null_cstr_max<R: Read + ?Sized, W: Write + ?Sized>(max: usize) 
+-> (impl ReadFn<R, CString>, impl WriteFn<W, CString>) {
+
+    (move |r: &mut R| {
+        let s = read_null_bytes(r, Some(max))?;
+
+        CString::new(s)
+            .map_err(|_| Error::from(BinError::CheckFail))
+    },
+    move |w: &mut W, s: &CString| {
+        match s.as_bytes_with_nul().len() <= max {
+            true => w.write_all(s.as_bytes_with_nul()),
+            false => Err(Error::from(BinError::StringTooLong))
+        }
+    })
+}
+
+/// Reads/Writes an utf8 string prefixed by its length 
+/// in bytes, read/written using the passed parser.
+/// 
+/// ## Reading
+/// The function reads the length using the passed parser,
+/// then reads the string.
+/// 
+/// ## Writing
+/// The function writes the length of the string using the
+/// passed parser (failing if it doesn't fit), then writes
+/// the string.
+/// 
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::numbers::{ be_u8, be_u32, varint_u32 };
+/// use bin_io::strings::prefixed_utf8;
+/// use bin_io::{ read, write };
+/// 
+/// // A Pascal string
+/// let vec = vec![ 0x03, 0x46, 0x6f, 0x6f ];
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// let string = read(&mut cursor, prefixed_utf8(be_u8()))
+///     .unwrap();
+/// 
+/// assert_eq!(string, "Foo");
+/// 
+/// // A .NET BinaryWriter string
+/// let vec = Vec::new();
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// write(&mut cursor, &"Bar".to_owned(), prefixed_utf8(varint_u32()))
+///     .unwrap();
+/// 
+/// assert_eq!(cursor.get_ref(), &[ 0x03, 0x42, 0x61, 0x72 ]);
+/// 
+/// // Too long for the prefix!
+/// let err = write(&mut cursor, &"a".repeat(256), prefixed_utf8(be_u8()));
+/// 
+/// assert!(err.is_err());
+/// 
+/// // A bogus length doesn't allocate upfront
+/// let vec = vec![ 0xff, 0xff, 0xff, 0xff, 0x46, 0x6f, 0x6f ];
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// let err = read(&mut cursor, prefixed_utf8(be_u32()));
+/// 
+/// assert!(err.is_err());
+/// ```
+pub fn prefixed_utf8<R: Read + ?Sized, W: Write + ?Sized, Rf, Wf, L>(len: (Rf, Wf)) 
+-> (impl ReadFn<R, String>, impl WriteFn<W, String>)
+where Rf: ReadFn<R, L>, Wf: WriteFn<W, L>, L: TryFrom<usize> + TryInto<usize> {
+
+    let (lr, lw) = try_cast(len);
+
+    (move |r: &mut R| {
+        let len = lr(r)?;
+        let s = read_len_bytes(r, len)?;
+
+        String::from_utf8(s)
+            .map_err(|e| Error::from(BinError::from(e)))
+    },
+    move |w: &mut W, s: &String| {
+        lw(w, &s.len())?;
+        w.write_all(s.as_bytes())
+    })
+}
+
+/// Reads/Writes an ascii string prefixed by its length, 
+/// read/written using the passed parser.
+/// 
+/// # Remarks
+/// The only difference between `prefixed_ascii` and
+/// `prefixed_utf8` is the ascii check that fails if the 
+/// string is not ascii.
+/// 
+/// # Errors
+/// If the input string is not ascii an error is returned.
+pub fn prefixed_ascii<R: Read + ?Sized, W: Write + ?Sized, Rf, Wf, L>(len: (Rf, Wf)) 
+-> (impl ReadFn<R, String>, impl WriteFn<W, String>)
+where Rf: ReadFn<R, L>, Wf: WriteFn<W, L>, L: TryFrom<usize> + TryInto<usize> {
+
+    let (rf, wf) = prefixed_utf8(len);
+
+    (move |r: &mut R| {
+        let s = rf(r)?;
+
+        match s.is_ascii() {
+            true => Ok(s),
+            false => Err(Error::from(BinError::CheckFail))
+        }
+    },
+    move |w: &mut W, s: &String| {
+        match s.is_ascii() {
+            true => wf(w, s),
+            false => Err(Error::from(BinError::CheckFail))
+        }
+    })
+}
+
+/// Reads/Writes an utf16 string prefixed by its length
+/// in code units, read/written using the passed parser.
+/// 
+/// # Remarks
+/// This is the same as `be_prefixed_utf16`.
+pub fn prefixed_utf16<R: Read + ?Sized, W: Write + ?Sized, Rf, Wf, L>(len: (Rf, Wf)) 
+-> (impl ReadFn<R, String>, impl WriteFn<W, String>)
+where Rf: ReadFn<R, L>, Wf: WriteFn<W, L>, L: TryFrom<usize> + TryInto<usize> {
+
+    be_prefixed_utf16(len)
+}
+
+/// Reads/Writes a big endian utf16 string prefixed by its 
+/// length in code units, read/written using the passed parser.
+pub fn be_prefixed_utf16<R: Read + ?Sized, W: Write + ?Sized, Rf, Wf, L>(len: (Rf, Wf)) 
+-> (impl ReadFn<R, String>, impl WriteFn<W, String>)
+where Rf: ReadFn<R, L>, Wf: WriteFn<W, L>, L: TryFrom<usize> + TryInto<usize> {
+
+    prefixed_utf16_with::<BigEndian, _, _, _, _, _>(len)
+}
+
+/// Reads/Writes a little endian utf16 string prefixed by its 
+/// length in code units, read/written using the passed parser.
+/// 
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::numbers::le_u16;
+/// use bin_io::strings::le_prefixed_utf16;
+/// use bin_io::write;
+/// 
+/// let vec = Vec::new();
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// write(&mut cursor, &"Hi".to_owned(), le_prefixed_utf16(le_u16()))
+///     .unwrap();
+/// 
+/// assert_eq!(cursor.get_ref(), &[ 0x02, 0x00, 0x48, 0x00, 0x69, 0x00 ]);
+/// ```
+pub fn le_prefixed_utf16<R: Read + ?Sized, W: Write + ?Sized, Rf, Wf, L>(len: (Rf, Wf)) 
+-> (impl ReadFn<R, String>, impl WriteFn<W, String>)
+where Rf: ReadFn<R, L>, Wf: WriteFn<W, L>, L: TryFrom<usize> + TryInto<usize> {
+
+    prefixed_utf16_with::<LittleEndian, _, _, _, _, _>(len)
+}
+
+fn decode_cesu8(s: &[u8], mutf8: bool) -> io::Result<String> {
+    let fail = || Error::from(BinError::EncodingFail);
+    let cont = |b: Option<&u8>| match b {
+        Some(b) if b & 0xc0 == 0x80 => Ok((b & 0x3f) as u16),
+        _ => Err(fail())
+    };
+
+    let mut units = Vec::with_capacity(s.len());
+    let mut iter = s.iter();
+
+    while let Some(b) = iter.next() {
+        let c = match b {
+            0x00 if mutf8 => return Err(fail()),
+            0x00..=0x7f => *b as u16,
+            0xc0..=0xdf => {
+                let c = ((b & 0x1f) as u16) << 6 | cont(iter.next())?;
+                match (c, mutf8) {
+                    (0, true) => 0,
+                    (0x80..=0x7ff, _) => c,
+                    _ => return Err(fail())
+                }
+            },
+            0xe0..=0xef => {
+                let c = ((b & 0x0f) as u16) << 12 | cont(iter.next())? << 6 | cont(iter.next())?;
+                match c {
+                    0x800..=0xffff => c,
+                    _ => return Err(fail())
+                }
+            },
+            _ => return Err(fail())
+        };
+
+        units.push(c);
+    }
+
+    String::from_utf16(&units[..])
+        .map_err(|e| Error::from(BinError::from(e)))
+}
+
+fn encode_cesu8(s: &str, mutf8: bool) -> Vec<u8> {
+    let mut vec = Vec::with_capacity(s.len());
+
+    for c in s.encode_utf16() {
+        match (c, mutf8) {
+            (0, true) => vec.extend_from_slice(&[ 0xc0, 0x80 ]),
+            (0x00..=0x7f, _) => vec.push(c as u8),
+            (0x80..=0x7ff, _) => vec.extend_from_slice(&[
+                0xc0 | (c >> 6) as u8,
+                0x80 | (c & 0x3f) as u8
+            ]),
+            _ => vec.extend_from_slice(&[
+                0xe0 | (c >> 12) as u8,
+                0x80 | ((c >> 6) & 0x3f) as u8,
+                0x80 | (c & 0x3f) as u8
+            ])
+        }
+    }
+
+    vec
+}
+
+fn null_cesu8_with<R: Read + ?Sized, W: Write + ?Sized>(mutf8: bool) 
+-> (impl ReadFn<R, String>, impl WriteFn<W, String>) {
+
+    (move |r: &mut R| {
+        let s = read_null_bytes(r, None)?;
+        decode_cesu8(&s[..], mutf8)
+    },
+    move |w: &mut W, s: &String| {
+        w.write_all(&encode_cesu8(s, mutf8)[..])?;
+        w.write_u8(0)
+    })
+}
+
+fn len_cesu8_with<R: Read + ?Sized, W: Write + ?Sized>(len: usize, mutf8: bool) 
+-> (impl ReadFn<R, String>, impl WriteFn<W, String>) {
+
+    (move |r: &mut R| {
+        let s = read_len_bytes(r, len)?;
+        decode_cesu8(&s[..], mutf8)
+    },
+    move |w: &mut W, s: &String| {
+        let s = encode_cesu8(s, mutf8);
+
+        match s.len() == len {
+            true => w.write_all(&s[..]),
+            false => Err(Error::from(BinError::LengthMismatch))
+        }
+    })
+}
+
+fn prefixed_cesu8_with<R: Read + ?Sized, W: Write + ?Sized, Rf, Wf, L>(len: (Rf, Wf), mutf8: bool) 
+-> (impl ReadFn<R, String>, impl WriteFn<W, String>)
+where Rf: ReadFn<R, L>, Wf: WriteFn<W, L>, L: TryFrom<usize> + TryInto<usize> {
+
+    let (lr, lw) = try_cast(len);
+
+    (move |r: &mut R| {
+        let len = lr(r)?;
+        read(r, len_cesu8_with(len, mutf8))
+    },
+    move |w: &mut W, s: &String| {
+        let s = encode_cesu8(s, mutf8);
+        lw(w, &s.len())?;
+        w.write_all(&s[..])
+    })
+}
+
+/// Reads/Writes a null terminated Java modified utf8 string
+/// (as used by JNI) from a stream.
+/// 
+/// Modified utf8 encodes the null character as `0xc0 0x80`, 
+/// and characters outside the BMP as two surrogates of 
+/// three bytes each.
+/// 
+/// # Remarks
+/// A raw null byte inside the string is rejected. Since 
+/// the result is a `String`, unpaired surrogates (which 
+/// Java strings may contain) are not supported either, 
+/// and an error is returned when one is read.
+/// 
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::strings::null_mutf8;
+/// use bin_io::write;
+/// 
+/// let vec = Vec::new();
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// write(&mut cursor, &"a\0".to_owned(), null_mutf8())
+///     .unwrap();
+/// 
+/// assert_eq!(cursor.get_ref(), &[ 0x61, 0xc0, 0x80, 0x00 ]);
+/// ```
+pub fn null_mutf8<R: Read + ?Sized, W: Write + ?Sized>() 
+-> (impl ReadFn<R, String>, impl WriteFn<W, String>) {
+
+    null_cesu8_with(true)
+}
+
+/// Reads/Writes a Java modified utf8 string from
+/// a stream given its length in bytes.
+/// 
+/// # Errors
+/// If the length of the encoded input string is not 
+/// `len` an error is returned.
+/// 
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::strings::len_mutf8;
+/// use bin_io::read;
+/// 
+/// // The null character must be encoded as 0xc0 0x80
+/// let vec = vec![ 0x61, 0x00, 0x62 ];
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// let err = read(&mut cursor, len_mutf8(3));
+/// 
+/// assert!(err.is_err());
+/// ```
+pub fn len_mutf8<R: Read + ?Sized, W: Write + ?Sized>(len: usize) 
+-> (impl ReadFn<R, String>, impl WriteFn<W, String>) {
+
+    len_cesu8_with(len, true)
+}
+
+/// Reads/Writes a Java modified utf8 string prefixed 
+/// by its length in bytes, read/written using the 
+/// passed parser.
+/// 
+/// With a `be_u16` prefix this is the format of 
+/// `DataOutput.writeUTF` and of the class file 
+/// constant pool.
+/// 
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::numbers::be_u16;
+/// use bin_io::strings::prefixed_mutf8;
+/// use bin_io::{ read, write };
+/// 
+/// let vec = Vec::new();
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// write(&mut cursor, &"A\0😀".to_owned(), prefixed_mutf8(be_u16()))
+///     .unwrap();
+/// 
+/// assert_eq!(cursor.get_ref(), &[ 
+///     0x00, 0x09, 
+///     0x41, 
+///     0xc0, 0x80, 
+///     0xed, 0xa0, 0xbd, 0xed, 0xb8, 0x80 
+/// ]);
+/// 
+/// cursor.set_position(0);
+/// 
+/// let string = read(&mut cursor, prefixed_mutf8(be_u16()))
+///     .unwrap();
+/// 
+/// assert_eq!(string, "A\0😀");
+/// ```
+pub fn prefixed_mutf8<R: Read + ?Sized, W: Write + ?Sized, Rf, Wf, L>(len: (Rf, Wf)) 
+-> (impl ReadFn<R, String>, impl WriteFn<W, String>)
+where Rf: ReadFn<R, L>, Wf: WriteFn<W, L>, L: TryFrom<usize> + TryInto<usize> {
+
+    prefixed_cesu8_with(len, true)
+}
+
+/// Reads/Writes a null terminated CESU-8 string from a stream.
+/// 
+/// CESU-8 encodes characters outside the BMP as two 
+/// surrogates of three bytes each.
+/// 
+/// # Remarks
+/// Unpaired surrogates can't be represented by a `String`,
+/// so an error is returned when one is read.
+/// 
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::strings::null_cesu8;
+/// use bin_io::read;
+/// 
+/// // A lone high surrogate
+/// let vec = vec![ 0xed, 0xa0, 0xbd, 0x00 ];
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// let err = read(&mut cursor, null_cesu8());
+/// 
+/// assert!(err.is_err());
+/// ```
+pub fn null_cesu8<R: Read + ?Sized, W: Write + ?Sized>() 
+-> (impl ReadFn<R, String>, impl WriteFn<W, String>) {
+
+    null_cesu8_with(false)
+}
+
+/// Reads/Writes a CESU-8 string from a stream 
+/// given its length in bytes.
+/// 
+/// # Errors
+/// If the length of the encoded input string is not 
+/// `len` an error is returned.
+/// 
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::strings::len_cesu8;
+/// use bin_io::read;
+/// 
+/// let vec = vec![ 0xed, 0xa0, 0xbd, 0xed, 0xb8, 0x80 ];
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// let string = read(&mut cursor, len_cesu8(6))
+///     .unwrap();
+/// 
+/// assert_eq!(string, "😀");
+/// ```
+pub fn len_cesu8<R: Read + ?Sized, W: Write + ?Sized>(len: usize) 
+-> (impl ReadFn<R, String>, impl WriteFn<W, String>) {
+
+    len_cesu8_with(len, false)
+}
+
+/// Reads/Writes a CESU-8 string prefixed by its length 
+/// in bytes, read/written using the passed parser.
+pub fn prefixed_cesu8<R: Read + ?Sized, W: Write + ?Sized, Rf, Wf, L>(len: (Rf, Wf)) 
+-> (impl ReadFn<R, String>, impl WriteFn<W, String>)
+where Rf: ReadFn<R, L>, Wf: WriteFn<W, L>, L: TryFrom<usize> + TryInto<usize> {
+
+    prefixed_cesu8_with(len, false)
+}
@@ -1,9 +1,25 @@
 //! Various utility functions.
 
-use crate::{ WriteFn, ReadFn, BinError };
-use std::io::{ Read, Write, Error };
+use crate::{ WriteFn, ReadFn, BinError, size };
+use std::io::{ self, Read, Write, Error, ErrorKind };
 use std::convert::{ TryInto, TryFrom };
 
+/// Maximum number of bytes preallocated by `count`, 
+/// since its length might come from untrusted data.
+pub(crate) const MAX_PREALLOC: usize = 1 << 16;
+
+/// Reads `len` bytes, growing the buffer as the
+/// bytes arrive instead of trusting `len` upfront.
+pub(crate) fn read_len_bytes<R: Read + ?Sized>(r: &mut R, len: usize) -> io::Result<Vec<u8>> {
+    let mut vec = Vec::new();
+    Read::take(&mut *r, len as u64).read_to_end(&mut vec)?;
+
+    match vec.len() == len {
+        true => Ok(vec),
+        false => Err(Error::from(ErrorKind::UnexpectedEof))
+    }
+}
+
 /// Binds a value to a writer/reader.
 /// 
 /// This is an helper function used in conjuction
@@ -35,7 +51,7 @@ use std::convert::{ TryInto, TryFrom };
 /// 
 /// assert_eq!(cursor.get_ref()[0], 0x50);
 /// ```
-pub fn bind<R: Read, W: Write, Rf, Wf, I>(f: (Rf, Wf), i: I) 
+pub fn bind<R: Read + ?Sized, W: Write + ?Sized, Rf, Wf, I>(f: (Rf, Wf), i: I) 
 -> (impl ReadFn<R, ()>, impl WriteFn<W, ()>)
 where Rf: ReadFn<R, I>, Wf: WriteFn<W, I>, I: PartialEq + Clone {
 
@@ -44,7 +60,8 @@ where Rf: ReadFn<R, I>, Wf: WriteFn<W, I>, I: PartialEq + Clone {
 
     (move |r: &mut R| {
 
-        match rf(r)?.eq(&ri) {
+        // The values made up by `fixed_size` can't match
+        match rf(r)?.eq(&ri) || size::querying() {
             true => Ok(()),
             false => Err(Error::from(BinError::CheckFail))
         }
@@ -91,7 +108,7 @@ where Rf: ReadFn<R, I>, Wf: WriteFn<W, I>, I: PartialEq + Clone {
 /// 
 /// assert_eq!(cursor.get_ref()[0], 0x50);
 /// ```
-pub fn skip<R: Read, W: Write, Rf, Wf, I>(f: (Rf, Wf), i: I) 
+pub fn skip<R: Read + ?Sized, W: Write + ?Sized, Rf, Wf, I>(f: (Rf, Wf), i: I) 
 -> (impl ReadFn<R, ()>, impl WriteFn<W, ()>)
 where Rf: ReadFn<R, I>, Wf: WriteFn<W, I>, I: Clone {
 
@@ -112,7 +129,10 @@ where Rf: ReadFn<R, I>, Wf: WriteFn<W, I>, I: Clone {
 /// ## Reading
 /// The function reads a number of values using
 /// the passed parser, the number of values read
-/// depends on the input value.
+/// depends on the input value. The vec is preallocated
+/// for up to 64 KiB of input, using the size reported by
+/// the parser for the first value (see `fixed_size`), and 
+/// grows as needed past that.
 /// 
 /// ## Writing
 /// The function checks the size of the array against
@@ -143,18 +163,29 @@ where Rf: ReadFn<R, I>, Wf: WriteFn<W, I>, I: Clone {
 /// 
 /// assert_eq!(cursor.get_ref(), &[ 10, 20, 30 ]);
 /// ```
-pub fn count<R: Read, W: Write, Rf, Wf, I>(f: (Rf, Wf), c: usize)
+pub fn count<R: Read + ?Sized, W: Write + ?Sized, Rf, Wf, I>(f: (Rf, Wf), c: usize)
 -> (impl ReadFn<R, Vec<I>>, impl WriteFn<W, Vec<I>>)
 where Rf: ReadFn<R, I>, Wf: WriteFn<W, I> {
 
     let (rf, wf) = f;
     
     (move |r: &mut R| {
-        
-        let mut vec = Vec::with_capacity(c);
-        
-        for _ in 0..c {
-            vec.push(rf(r)?);
+
+        let mut vec = Vec::new();
+
+        for i in 0..c {
+            match i {
+                0 => {
+                    let (v, size) = size::measure(|| rf(r));
+                    let size = size.filter(|&s| s > 0)
+                        .unwrap_or_else(std::mem::size_of::<I>)
+                        .max(1);
+
+                    vec.reserve_exact(c.min(MAX_PREALLOC / size));
+                    vec.push(v?);
+                },
+                _ => vec.push(rf(r)?)
+            }
         }
 
         Ok(vec)
@@ -174,6 +205,57 @@ where Rf: ReadFn<R, I>, Wf: WriteFn<W, I> {
     })
 }
 
+/// Reads/Writes a fixed size array of values.
+/// 
+/// ## Reading
+/// The function reads `N` values using the passed parser.
+/// 
+/// ## Writing
+/// The function writes the whole array.
+/// 
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::numbers::{ be_u16 };
+/// use bin_io::{ read, array };
+/// 
+/// let vec = vec![ 0x00, 0x10, 0x00, 0x20 ];
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// let a: [u16; 2] = read(&mut cursor, array(be_u16()))
+///     .unwrap();
+/// 
+/// assert_eq!(a, [ 0x10, 0x20 ]);
+/// ```
+pub fn array<R: Read + ?Sized, W: Write + ?Sized, Rf, Wf, I, const N: usize>(f: (Rf, Wf))
+-> (impl ReadFn<R, [I; N]>, impl WriteFn<W, [I; N]>)
+where Rf: ReadFn<R, I>, Wf: WriteFn<W, I> {
+
+    let (rf, wf) = f;
+
+    (move |r: &mut R| {
+
+        let mut vec = Vec::with_capacity(N);
+
+        for _ in 0..N {
+            vec.push(rf(r)?);
+        }
+
+        match <[I; N]>::try_from(vec) {
+            Ok(a) => Ok(a),
+            Err(_) => unreachable!()
+        }
+    },
+    move |w: &mut W, v: &[I; N]| {
+
+        for e in v {
+            wf(w, e)?;
+        }
+
+        Ok(())
+    })
+}
+
 /// Bidirectional cast.
 /// 
 /// This is an helper function used in conjuction
@@ -189,7 +271,7 @@ where Rf: ReadFn<R, I>, Wf: WriteFn<W, I> {
 /// Since this cast is bidirectional, each type must
 /// be constructible from the other. Therefore remember
 /// to implement `From<I> for O` and `From<O> for I`!
-pub fn cast<R: Read, W: Write, Rf, Wf, I, O>(f: (Rf, Wf))
+pub fn cast<R: Read + ?Sized, W: Write + ?Sized, Rf, Wf, I, O>(f: (Rf, Wf))
 -> (impl ReadFn<R, I>, impl WriteFn<W, I>)
 where Rf: ReadFn<R, O>, Wf: WriteFn<W, O>, O: From<I> + Into<I>, I: Clone {
 
@@ -244,7 +326,7 @@ where Rf: ReadFn<R, O>, Wf: WriteFn<W, O>, O: From<I> + Into<I>, I: Clone {
 ///     a: be_u8() =>
 ///     b: optional(
 ///         be_u8(),
-///         a.to_owned() != 0
+///         a != 0
 ///     ) =>
 /// );
 /// 
@@ -253,13 +335,15 @@ where Rf: ReadFn<R, O>, Wf: WriteFn<W, O>, O: From<I> + Into<I>, I: Clone {
 /// 
 /// assert_eq!(unicorn, Unicorn { a: 0, b: None });
 /// ```
-pub fn optional<R: Read, W: Write, Rf, Wf, I>(f: (Rf, Wf), c: bool)
+pub fn optional<R: Read + ?Sized, W: Write + ?Sized, Rf, Wf, I>(f: (Rf, Wf), c: bool)
 -> (impl ReadFn<R, Option<I>>, impl WriteFn<W, Option<I>>)
 where Rf: ReadFn<R, I>, Wf: WriteFn<W, I> {
 
     let (rf, wf) = f;
 
     (move |r: &mut R| {
+        size::variable()?;
+
         match c {
             true => Ok(Some(rf(r)?)),
             false => Ok(None)
@@ -321,7 +405,7 @@ where Rf: ReadFn<R, I>, Wf: WriteFn<W, I> {
 /// assert!(err.is_err());
 /// 
 /// ```
-pub fn try_cast<R: Read, W: Write, Rf, Wf, I, O>(f: (Rf, Wf))
+pub fn try_cast<R: Read + ?Sized, W: Write + ?Sized, Rf, Wf, I, O>(f: (Rf, Wf))
 -> (impl ReadFn<R, I>, impl WriteFn<W, I>)
 where Rf: ReadFn<R, O>, Wf: WriteFn<W, O>, O: TryFrom<I> + TryInto<I>, I: Clone {
 
@@ -366,7 +450,7 @@ where Rf: ReadFn<R, O>, Wf: WriteFn<W, O>, O: TryFrom<I> + TryInto<I>, I: Clone
 /// 
 /// assert_eq!(thing.a, false);
 /// ```
-pub fn boolean<R: Read, W: Write, Rf, Wf, I>(f: (Rf, Wf), true_val: I, false_val: I)
+pub fn boolean<R: Read + ?Sized, W: Write + ?Sized, Rf, Wf, I>(f: (Rf, Wf), true_val: I, false_val: I)
 -> (impl ReadFn<R, bool>, impl WriteFn<W, bool>)
 where Rf: ReadFn<R, I>, Wf: WriteFn<W, I>, I: PartialEq + Clone {
 
@@ -387,4 +471,185 @@ where Rf: ReadFn<R, I>, Wf: WriteFn<W, I>, I: PartialEq + Clone {
             false => &wfalse_val
         })
     })
-}
\ No newline at end of file
+}
+
+/// Maps the values read by a parser to the 
+/// variants of a C-like enum.
+/// 
+/// ## Reading
+/// The function reads a value and returns the matching 
+/// variant from `table`, if there is none an error is returned.
+/// 
+/// ## Writing
+/// The function writes the value matching the variant,
+/// if there is none an error is returned.
+/// 
+/// # Remarks
+/// For enums you own, `bin_enum!` generates the same 
+/// mapping (with an optional catch-all variant) to be 
+/// used with `try_cast`.
+/// 
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::numbers::be_u8;
+/// use bin_io::{ read, write, enum_repr };
+/// 
+/// #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// enum Shape {
+///     Circle,
+///     Square
+/// }
+/// 
+/// let vec = Vec::new();
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// let table = &[ (1, Shape::Circle), (4, Shape::Square) ];
+/// 
+/// write(&mut cursor, &Shape::Square, enum_repr(be_u8(), table))
+///     .unwrap();
+/// 
+/// assert_eq!(cursor.get_ref(), &[ 0x04 ]);
+/// 
+/// cursor.set_position(0);
+/// 
+/// let shape = read(&mut cursor, enum_repr(be_u8(), table))
+///     .unwrap();
+/// 
+/// assert_eq!(shape, Shape::Square);
+/// ```
+pub fn enum_repr<R: Read + ?Sized, W: Write + ?Sized, Rf, Wf, I, E>(f: (Rf, Wf), table: &'static [(I, E)])
+-> (impl ReadFn<R, E>, impl WriteFn<W, E>)
+where Rf: ReadFn<R, I>, Wf: WriteFn<W, I>, I: PartialEq, E: PartialEq + Clone {
+
+    let (rf, wf) = f;
+
+    (move |r: &mut R| {
+        let i = rf(r)?;
+
+        table.iter()
+            .find(|(v, _)| *v == i)
+            .map(|(_, e)| e.clone())
+            .ok_or_else(|| Error::from(BinError::UnknownVariant))
+    },
+    move |w: &mut W, e: &E| {
+        match table.iter().find(|(_, v)| v == e) {
+            Some((i, _)) => wf(w, i),
+            None => Err(Error::from(BinError::UnknownVariant))
+        }
+    })
+}
+
+/// Reads/Writes a set of flags defined with the `bitflags` crate.
+/// 
+/// This function is only available with the `bitflags` feature.
+/// 
+/// ## Reading
+/// The function reads the bits, if any of them doesn't 
+/// correspond to a defined flag an error is returned.
+/// 
+/// ## Writing
+/// The function writes the bits, if any of them doesn't 
+/// correspond to a defined flag an error is returned.
+/// 
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::numbers::be_u8;
+/// use bin_io::{ read, flags };
+/// use bitflags::bitflags;
+/// 
+/// bitflags! {
+///     #[derive(Debug, PartialEq, Eq)]
+///     struct Access: u8 {
+///         const READ = 0b001;
+///         const WRITE = 0b010;
+///     }
+/// }
+/// 
+/// let vec = vec![ 0b011, 0b111 ];
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// let access: Access = read(&mut cursor, flags(be_u8()))
+///     .unwrap();
+/// 
+/// assert_eq!(access, Access::READ | Access::WRITE);
+/// 
+/// // 0b100 is not a known flag
+/// let err = read::<_, _, _, Access>(&mut cursor, flags(be_u8()));
+/// 
+/// assert!(err.is_err());
+/// ```
+#[cfg(feature = "bitflags")]
+pub fn flags<R: Read + ?Sized, W: Write + ?Sized, Rf, Wf, F>(f: (Rf, Wf))
+-> (impl ReadFn<R, F>, impl WriteFn<W, F>)
+where Rf: ReadFn<R, F::Bits>, Wf: WriteFn<W, F::Bits>, F: bitflags::Flags {
+
+    let (rf, wf) = f;
+
+    (move |r: &mut R| {
+        F::from_bits(rf(r)?)
+            .ok_or_else(|| Error::from(BinError::UnknownFlags))
+    },
+    move |w: &mut W, i: &F| {
+        match F::from_bits(i.bits()) {
+            Some(_) => wf(w, &i.bits()),
+            None => Err(Error::from(BinError::UnknownFlags))
+        }
+    })
+}
+
+/// Reads/Writes a set of flags defined with the `bitflags` crate,
+/// preserving unknown bits.
+/// 
+/// This function is only available with the `bitflags` feature.
+/// 
+/// This is the lenient variant of `flags`, bits that don't 
+/// correspond to a defined flag are kept as they are, so the 
+/// value round-trips unchanged.
+/// 
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use bin_io::numbers::be_u8;
+/// use bin_io::{ read, write, flags_retain };
+/// use bitflags::bitflags;
+/// 
+/// bitflags! {
+///     #[derive(Debug, PartialEq, Eq)]
+///     struct Access: u8 {
+///         const READ = 0b001;
+///         const WRITE = 0b010;
+///     }
+/// }
+/// 
+/// let vec = vec![ 0b101 ];
+/// let mut cursor = Cursor::new(vec);
+/// 
+/// let access: Access = read(&mut cursor, flags_retain(be_u8()))
+///     .unwrap();
+/// 
+/// assert!(access.contains(Access::READ));
+/// assert_eq!(access.bits(), 0b101);
+/// 
+/// cursor.set_position(0);
+/// 
+/// write(&mut cursor, &access, flags_retain(be_u8()))
+///     .unwrap();
+/// 
+/// assert_eq!(cursor.get_ref(), &[ 0b101 ]);
+/// ```
+#[cfg(feature = "bitflags")]
+pub fn flags_retain<R: Read + ?Sized, W: Write + ?Sized, Rf, Wf, F>(f: (Rf, Wf))
+-> (impl ReadFn<R, F>, impl WriteFn<W, F>)
+where Rf: ReadFn<R, F::Bits>, Wf: WriteFn<W, F::Bits>, F: bitflags::Flags {
+
+    let (rf, wf) = f;
+
+    (move |r: &mut R| {
+        Ok(F::from_bits_retain(rf(r)?))
+    },
+    move |w: &mut W, i: &F| {
+        wf(w, &i.bits())
+    })
+}